@@ -0,0 +1,134 @@
+//! Accounts of a zeta staking deployment, for the `claim_stake` runs.
+//!
+//! The staking program cannot be set up from the benchmark, so `claim_stake` runs against accounts
+//! dumped from a cluster where it is, e.g. the localnet of `anchor test --detach`. The directory
+//! named by `ZETA_STAKING_FIXTURES` holds:
+//!
+//! - `zeta_staking.so`: the staking program.
+//! - `protocol_state.json`, `zeta_mint.json` and `stake_account_manager.json`: the accounts, as
+//!   written by `solana account <address> --output json`.
+//! - `claimant.json`: keypair of the stake account manager's authority.
+//! - `stake.json`: an unused stake account slot of that manager, see [StakeSlot].
+//!
+//! Without it the `claim_stake` runs are skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::AccountDeserialize;
+use base64::Engine;
+use serde::Deserialize;
+use solana_sdk::account::Account;
+use solana_sdk::bpf_loader;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+
+/// Environment variable naming the fixtures directory.
+pub const FIXTURES_ENV: &str = "ZETA_STAKING_FIXTURES";
+
+/// A stake account slot to stake the claim into.
+#[derive(Deserialize)]
+pub struct StakeSlot {
+    pub stake_account: String,
+    pub stake_vault: String,
+    pub bit_to_use: u8,
+    pub name: String,
+}
+
+/// A dumped account.
+pub struct Fixture {
+    pub address: Pubkey,
+    pub account: Account,
+}
+
+/// Everything `claim_stake` needs besides the distributor.
+pub struct ZetaStaking {
+    pub program: Account,
+    pub protocol_state: Fixture,
+    pub zeta_mint: Fixture,
+    pub stake_account_manager: Fixture,
+    pub claimant: Keypair,
+    pub stake_account: Pubkey,
+    pub stake_vault: Pubkey,
+    pub bit_to_use: u8,
+    pub stake_account_name: String,
+    /// Shortest stake duration the protocol accepts.
+    pub stake_duration_epochs: u32,
+}
+
+impl ZetaStaking {
+    /// Loads the fixtures, or returns `None` if [FIXTURES_ENV] is not set.
+    pub fn load() -> Option<Self> {
+        let dir = PathBuf::from(std::env::var_os(FIXTURES_ENV)?);
+
+        let elf = fs::read(dir.join("zeta_staking.so")).expect("zeta_staking.so");
+        let program = Account {
+            lamports: Rent::default().minimum_balance(elf.len()),
+            data: elf,
+            owner: bpf_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        };
+
+        let protocol_state = read_fixture(&dir.join("protocol_state.json"));
+        let state = zeta_staking::state::ProtocolState::try_deserialize(
+            &mut protocol_state.account.data.as_slice(),
+        )
+        .expect("protocol state");
+
+        let slot: StakeSlot =
+            serde_json::from_slice(&fs::read(dir.join("stake.json")).expect("stake.json"))
+                .expect("stake.json");
+
+        Some(Self {
+            program,
+            protocol_state,
+            zeta_mint: read_fixture(&dir.join("zeta_mint.json")),
+            stake_account_manager: read_fixture(&dir.join("stake_account_manager.json")),
+            claimant: read_keypair_file(dir.join("claimant.json")).expect("claimant.json"),
+            stake_account: Pubkey::from_str(&slot.stake_account).expect("stake_account"),
+            stake_vault: Pubkey::from_str(&slot.stake_vault).expect("stake_vault"),
+            bit_to_use: slot.bit_to_use,
+            stake_account_name: slot.name,
+            stake_duration_epochs: state.min_stake_duration_epochs,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DumpedAccount {
+    lamports: u64,
+    data: (String, String),
+    owner: String,
+    executable: bool,
+}
+
+#[derive(Deserialize)]
+struct Dump {
+    pubkey: String,
+    account: DumpedAccount,
+}
+
+fn read_fixture(path: &Path) -> Fixture {
+    let dump: Dump = serde_json::from_slice(
+        &fs::read(path).unwrap_or_else(|err| panic!("{}: {err}", path.display())),
+    )
+    .unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+    assert_eq!(dump.account.data.1, "base64", "{}", path.display());
+
+    Fixture {
+        address: Pubkey::from_str(&dump.pubkey).expect("pubkey"),
+        account: Account {
+            lamports: dump.account.lamports,
+            data: base64::engine::general_purpose::STANDARD
+                .decode(&dump.account.data.0)
+                .expect("base64 data"),
+            owner: Pubkey::from_str(&dump.account.owner).expect("owner"),
+            executable: dump.account.executable,
+            rent_epoch: 0,
+        },
+    }
+}
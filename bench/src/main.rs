@@ -0,0 +1,438 @@
+//! Compute unit benchmarks for the claim paths of the merkle distributor.
+//!
+//! Runs [merkle_distributor::merkle_distributor::claim] and
+//! [merkle_distributor::merkle_distributor::claim_stake] against the BPF build in `target/deploy`
+//! (`anchor build`), across proof depths, hash functions and claim status states. Prints the
+//! compute units and transaction size of every run and the deepest proof that still fits a single
+//! transaction, and exits with an error if any run goes over its [budget].
+//!
+//! ```sh
+//! anchor build && cargo run --release --manifest-path bench/Cargo.toml
+//! ```
+
+mod fixtures;
+
+use std::process::ExitCode;
+
+use anchor_lang::{AccountSerialize, InstructionData, ToAccountMetas};
+use merkle_distributor::claim::{ClaimLeaf, LeafVersion};
+use merkle_distributor::merkle_proof::{HashFunction, HashScheme};
+use merkle_distributor::{ClaimStatus, MerkleDistributor, PERCENT_100};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::program_option::COption;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::fixtures::ZetaStaking;
+
+/// Proof depths to run; a tree of `2^depth` leaves needs proofs of `depth` hashes.
+const DEPTHS: [usize; 8] = [1, 2, 4, 8, 12, 16, 20, 24];
+
+const HASH_FUNCTIONS: [HashFunction; 3] = [
+    HashFunction::Keccak,
+    HashFunction::Sha256,
+    HashFunction::Poseidon,
+];
+
+/// Compute units available to a transaction without a compute budget instruction.
+const DEFAULT_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// Amount of the benchmarked leaf.
+const LEAF_AMOUNT: u64 = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Ix {
+    Claim,
+    ClaimStake,
+}
+
+/// State of the claimant's [ClaimStatus] before the claim.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ClaimStatusState {
+    /// First claim; the claim status is created.
+    New,
+    /// Claimed before, then the allocation was raised by a root update.
+    Existing,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Scenario {
+    ix: Ix,
+    hash_function: HashFunction,
+    claim_status: ClaimStatusState,
+    depth: usize,
+}
+
+/// Most compute units `scenario` may use.
+///
+/// Ceilings with headroom rather than exact costs: the fixed part covers the Anchor account
+/// checks, the token transfer and creating the claim status, the per level part one hash of the
+/// proof.
+fn budget(scenario: &Scenario) -> u64 {
+    let base = match scenario.claim_status {
+        ClaimStatusState::New => 40_000,
+        ClaimStatusState::Existing => 30_000,
+    };
+    let stake = match scenario.ix {
+        Ix::Claim => 0,
+        Ix::ClaimStake => 60_000,
+    };
+    let per_level = match scenario.hash_function {
+        HashFunction::Keccak | HashFunction::Sha256 => 500,
+        HashFunction::Poseidon => 2_500,
+    };
+    base + stake + per_level * scenario.depth as u64
+}
+
+struct Measurement {
+    units: u64,
+    tx_size: usize,
+}
+
+impl Measurement {
+    fn fits(&self) -> bool {
+        self.units <= DEFAULT_COMPUTE_UNIT_LIMIT && self.tx_size <= PACKET_DATA_SIZE
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    if std::env::var_os("SBF_OUT_DIR").is_none() {
+        std::env::set_var(
+            "SBF_OUT_DIR",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../target/deploy"),
+        );
+    }
+
+    let zeta_staking = ZetaStaking::load();
+    if zeta_staking.is_none() {
+        println!(
+            "{} is not set, skipping claim_stake\n",
+            fixtures::FIXTURES_ENV
+        );
+    }
+
+    let mut over_budget = 0;
+    println!(
+        "{:<12} {:<9} {:<9} {:>5} {:>9} {:>9} {:>8}",
+        "ix", "hash", "status", "depth", "units", "budget", "tx bytes"
+    );
+    for ix in [Ix::Claim, Ix::ClaimStake] {
+        if ix == Ix::ClaimStake && zeta_staking.is_none() {
+            continue;
+        }
+        for hash_function in HASH_FUNCTIONS {
+            for claim_status in [ClaimStatusState::New, ClaimStatusState::Existing] {
+                let mut max_depth = None;
+                for depth in DEPTHS {
+                    let scenario = Scenario {
+                        ix,
+                        hash_function,
+                        claim_status,
+                        depth,
+                    };
+                    let measurement = run(&scenario, zeta_staking.as_ref()).await;
+                    let budget = budget(&scenario);
+                    println!(
+                        "{:<12} {:<9} {:<9} {:>5} {:>9} {:>9} {:>8}{}",
+                        format!("{ix:?}"),
+                        format!("{hash_function:?}"),
+                        format!("{claim_status:?}"),
+                        depth,
+                        measurement.units,
+                        budget,
+                        measurement.tx_size,
+                        if measurement.units > budget {
+                            "  OVER BUDGET"
+                        } else {
+                            ""
+                        }
+                    );
+                    if measurement.units > budget {
+                        over_budget += 1;
+                    }
+                    if measurement.fits() {
+                        max_depth = Some(depth);
+                    }
+                }
+                match max_depth {
+                    Some(depth) => println!(
+                        "  fits one transaction up to depth {depth} ({} leaves)\n",
+                        1u64 << depth
+                    ),
+                    None => println!("  does not fit one transaction\n"),
+                }
+            }
+        }
+    }
+
+    if over_budget > 0 {
+        println!("{over_budget} runs over budget");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Sets up a distributor whose tree proves the claimant's leaf with a proof of `scenario.depth`
+/// hashes, then simulates the claim.
+async fn run(scenario: &Scenario, zeta_staking: Option<&ZetaStaking>) -> Measurement {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("merkle_distributor", merkle_distributor::ID, None);
+
+    let claimant = match (scenario.ix, zeta_staking) {
+        (Ix::ClaimStake, Some(zeta)) => zeta.claimant.insecure_clone(),
+        _ => Keypair::new(),
+    };
+    program_test.add_account(
+        claimant.pubkey(),
+        Account::new(LAMPORTS_PER_SOL, 0, &system_program::ID),
+    );
+
+    let mint = match (scenario.ix, zeta_staking) {
+        (Ix::ClaimStake, Some(zeta)) => {
+            program_test.add_account(zeta_staking::ID, zeta.program.clone());
+            for fixture in [
+                &zeta.protocol_state,
+                &zeta.zeta_mint,
+                &zeta.stake_account_manager,
+            ] {
+                program_test.add_account(fixture.address, fixture.account.clone());
+            }
+            zeta.zeta_mint.address
+        }
+        _ => {
+            let mint = Pubkey::new_unique();
+            program_test.add_account(mint, mint_account(LEAF_AMOUNT));
+            mint
+        }
+    };
+
+    let base = Pubkey::new_unique();
+    let (distributor_key, bump) = Pubkey::find_program_address(
+        &[b"MerkleDistributor".as_ref(), base.as_ref()],
+        &merkle_distributor::ID,
+    );
+    let mut distributor = MerkleDistributor {
+        base,
+        admin_auth: Pubkey::new_unique(),
+        bump,
+        mint,
+        max_total_claim: LEAF_AMOUNT,
+        max_num_nodes: 1,
+        claim_start_ts: 0,
+        claim_end_ts: u64::MAX,
+        immediate_claim_percentage: PERCENT_100,
+        hash_scheme: HashScheme::Tagged,
+        leaf_version: LeafVersion::V1,
+        hash_function: scenario.hash_function,
+        ..MerkleDistributor::default()
+    };
+    let leaf = ClaimLeaf {
+        index: 0,
+        claimant: claimant.pubkey(),
+        amount: LEAF_AMOUNT,
+        terms: None,
+    };
+    let proof: Vec<[u8; 32]> = (0..scenario.depth).map(|i| [i as u8 + 1; 32]).collect();
+    distributor.root = proof
+        .iter()
+        .try_fold(leaf.hash(&distributor).unwrap(), |node, sibling| {
+            distributor
+                .hash_scheme
+                .hash_pair(&distributor.hash_function, node, *sibling)
+        })
+        .unwrap();
+    program_test.add_account(
+        distributor_key,
+        program_account(&distributor, 8 + MerkleDistributor::LEN),
+    );
+
+    let (claim_status_key, _) = Pubkey::find_program_address(
+        &[
+            b"ClaimStatus".as_ref(),
+            distributor_key.as_ref(),
+            claimant.pubkey().as_ref(),
+        ],
+        &merkle_distributor::ID,
+    );
+    if scenario.claim_status == ClaimStatusState::Existing {
+        let claim_status = ClaimStatus {
+            claimant: claimant.pubkey(),
+            claimed_at: 1,
+            claimed_amount: LEAF_AMOUNT / 2,
+            num_claims: 1,
+            payer: claimant.pubkey(),
+            ..ClaimStatus::default()
+        };
+        program_test.add_account(
+            claim_status_key,
+            program_account(&claim_status, 8 + ClaimStatus::LEN),
+        );
+    }
+
+    let from = get_associated_token_address(&distributor_key, &mint);
+    let to = get_associated_token_address(&claimant.pubkey(), &mint);
+    program_test.add_account(from, token_account(&mint, &distributor_key, LEAF_AMOUNT));
+    program_test.add_account(to, token_account(&mint, &claimant.pubkey(), 0));
+
+    let ix = match (scenario.ix, zeta_staking) {
+        (Ix::ClaimStake, Some(zeta)) => Instruction {
+            program_id: merkle_distributor::ID,
+            accounts: merkle_distributor::accounts::ClaimStake {
+                distributor: distributor_key,
+                claim_status: claim_status_key,
+                from,
+                to,
+                zeta_staking: zeta_staking::ID,
+                cpi_protocol_state: zeta.protocol_state.address,
+                cpi_stake_account_manager: zeta.stake_account_manager.address,
+                cpi_stake_account: zeta.stake_account,
+                cpi_stake_vault: zeta.stake_vault,
+                zeta_mint: mint,
+                claimant: claimant.pubkey(),
+                payer: claimant.pubkey(),
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                screening_flag: None,
+                rebind: None,
+                leaf_screening_flag: None,
+            }
+            .to_account_metas(None),
+            data: merkle_distributor::instruction::ClaimStake {
+                index: leaf.index,
+                amount: leaf.amount,
+                terms: leaf.terms,
+                proof,
+                zeta_stake_bit_to_use: zeta.bit_to_use,
+                stake_acc_name: zeta.stake_account_name.clone(),
+                stake_duration_epochs: zeta.stake_duration_epochs,
+            }
+            .data(),
+        },
+        _ => Instruction {
+            program_id: merkle_distributor::ID,
+            accounts: merkle_distributor::accounts::Claim {
+                distributor: distributor_key,
+                claim_status: claim_status_key,
+                from,
+                to,
+                claimant: claimant.pubkey(),
+                payer: claimant.pubkey(),
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                screening_flag: None,
+                rebind: None,
+                leaf_screening_flag: None,
+            }
+            .to_account_metas(None),
+            data: merkle_distributor::instruction::Claim {
+                index: leaf.index,
+                amount: leaf.amount,
+                terms: leaf.terms,
+                proof,
+            }
+            .data(),
+        },
+    };
+
+    let mut context = program_test.start_with_context().await;
+    measure(&mut context, ix, &claimant).await
+}
+
+/// Simulates `ix` with the maximum compute unit limit, so deep proofs still report their cost.
+/// The reported size is that of the transaction without the compute budget instruction.
+async fn measure(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    payer: &Keypair,
+) -> Measurement {
+    let tx_size = bincode::serialized_size(&Transaction::new_signed_with_payer(
+        &[ix.clone()],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    ))
+    .unwrap() as usize;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+            ix,
+        ],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    let details = simulation.simulation_details.unwrap();
+    if let Some(Err(err)) = simulation.result {
+        panic!("claim failed: {err}\n{}", details.logs.join("\n"));
+    }
+
+    Measurement {
+        units: details.units_consumed,
+        tx_size,
+    }
+}
+
+fn program_account<T: AccountSerialize>(value: &T, space: usize) -> Account {
+    let mut data = vec![0; space];
+    value.try_serialize(&mut data.as_mut_slice()).unwrap();
+    Account {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: merkle_distributor::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn mint_account(supply: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,62 @@
+//! Reading transactions straight from a validator ledger.
+//!
+//! The ledger's Blockstore is opened as a secondary, so a validator may keep running on it. Every
+//! rooted block still in the ledger is read and its transactions converted to the `getTransaction`
+//! `json` encoding the rest of the indexer reads, see [crate::input].
+
+use std::fmt::Display;
+use std::io;
+use std::path::Path;
+
+use solana_ledger::blockstore::Blockstore;
+use solana_ledger::blockstore_db::BlockstoreError;
+use solana_ledger::blockstore_options::{AccessType, BlockstoreOptions};
+use solana_transaction_status::{
+    ConfirmedTransactionWithStatusMeta, TransactionWithStatusMeta, UiTransactionEncoding,
+};
+
+use crate::input::{parse_transaction, Transaction};
+
+/// Reads the transactions of the rooted blocks in the ledger at `path`.
+pub fn read_blockstore(path: &Path, transactions: &mut Vec<Transaction>) -> io::Result<()> {
+    let blockstore = Blockstore::open_with_options(
+        path,
+        BlockstoreOptions {
+            access_type: AccessType::Secondary,
+            enforce_ulimit_nofile: false,
+            ..BlockstoreOptions::default()
+        },
+    )
+    .map_err(|err| ledger_error(path, err))?;
+
+    let slots = blockstore
+        .rooted_slot_iterator(blockstore.lowest_slot())
+        .map_err(|err| ledger_error(path, err))?;
+    for slot in slots {
+        let block = match blockstore.get_rooted_block(slot, false) {
+            Ok(block) => block,
+            // Rooted but never replayed here, or already purged.
+            Err(BlockstoreError::SlotUnavailable | BlockstoreError::SlotCleanedUp) => continue,
+            Err(err) => return Err(ledger_error(path, err)),
+        };
+        for tx in block.transactions {
+            let encoded = ConfirmedTransactionWithStatusMeta {
+                slot,
+                tx_with_meta: TransactionWithStatusMeta::Complete(tx),
+                block_time: block.block_time,
+            }
+            .encode(UiTransactionEncoding::Json, Some(0))
+            .map_err(|err| ledger_error(path, err))?;
+            let value = serde_json::to_value(encoded).map_err(|err| ledger_error(path, err))?;
+            transactions.push(parse_transaction(value).map_err(|err| ledger_error(path, err))?);
+        }
+    }
+    Ok(())
+}
+
+fn ledger_error(path: &Path, err: impl Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {err}", path.display()),
+    )
+}
@@ -0,0 +1,322 @@
+//! Decoding of the program's events from transaction log messages.
+//!
+//! Anchor's `emit!` logs each event as `Program data: <base64>`, the base64 of its discriminator
+//! followed by its Borsh serialization. Other programs log the same way, so only data logged while
+//! the merkle distributor is the innermost running program is decoded.
+
+use std::io;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+use merkle_distributor::{
+    AdminAuthUpdatedEvent, AdminClaimedAfterExpiryEvent, ClaimDelegateRemovedEvent,
+    ClaimDelegateSetEvent, ClaimPercentagesUpdatedEvent, ClaimReboundEvent, ClaimRevokedEvent,
+    ClaimStatusMigratedEvent, ClaimTargetAddedEvent, ClaimTargetRemovedEvent,
+    ClaimWindowUpdatedEvent, ClaimedEvent, DistributorCreatedEvent, DistributorMigratedEvent,
+    GracePeriodUpdatedEvent, RateLimitTrippedEvent, RateLimitsUpdatedEvent, RootUpdatedEvent,
+    ScreeningProgramUpdatedEvent, StakeClaimScalingUpdatedEvent, SweepRecipientUpdatedEvent,
+    SweptEvent,
+};
+
+/// [ClaimedEvent] as logged before it named its distributor and what was transferred.
+#[derive(Clone, Debug)]
+pub struct LegacyClaimedEvent {
+    pub root: [u8; 32],
+    pub index: u64,
+    pub claimant: Pubkey,
+    pub claim_amount: u64,
+}
+
+impl LegacyClaimedEvent {
+    fn deserialize(data: &mut &[u8]) -> io::Result<Self> {
+        Ok(Self {
+            root: AnchorDeserialize::deserialize(data)?,
+            index: AnchorDeserialize::deserialize(data)?,
+            claimant: AnchorDeserialize::deserialize(data)?,
+            claim_amount: AnchorDeserialize::deserialize(data)?,
+        })
+    }
+}
+
+/// An event of the merkle distributor program.
+pub enum DistributorEvent {
+    Claimed(ClaimedEvent),
+    /// A [LegacyClaimedEvent], with the distributor of its transaction if it could be told.
+    LegacyClaimed {
+        event: LegacyClaimedEvent,
+        distributor: Option<Pubkey>,
+    },
+    DistributorCreated(DistributorCreatedEvent),
+    DistributorMigrated(DistributorMigratedEvent),
+    RootUpdated(RootUpdatedEvent),
+    ClaimWindowUpdated(ClaimWindowUpdatedEvent),
+    ClaimPercentagesUpdated(ClaimPercentagesUpdatedEvent),
+    GracePeriodUpdated(GracePeriodUpdatedEvent),
+    ScreeningProgramUpdated(ScreeningProgramUpdatedEvent),
+    RateLimitsUpdated(RateLimitsUpdatedEvent),
+    RateLimitTripped(RateLimitTrippedEvent),
+    StakeClaimScalingUpdated(StakeClaimScalingUpdatedEvent),
+    AdminAuthUpdated(AdminAuthUpdatedEvent),
+    ClaimTargetAdded(ClaimTargetAddedEvent),
+    ClaimTargetRemoved(ClaimTargetRemovedEvent),
+    ClaimRebound(ClaimReboundEvent),
+    ClaimRevoked(ClaimRevokedEvent),
+    AdminClaimedAfterExpiry(AdminClaimedAfterExpiryEvent),
+    SweepRecipientUpdated(SweepRecipientUpdatedEvent),
+    Swept(SweptEvent),
+    ClaimDelegateSet(ClaimDelegateSetEvent),
+    ClaimDelegateRemoved(ClaimDelegateRemovedEvent),
+    ClaimStatusMigrated(ClaimStatusMigratedEvent),
+}
+
+impl DistributorEvent {
+    /// Decodes an event from its logged data. Returns `Ok(None)` if it is not one of ours, and an
+    /// error if it is but does not deserialize.
+    pub fn decode(data: &[u8]) -> io::Result<Option<Self>> {
+        if data.len() < 8 {
+            return Ok(None);
+        }
+        let (discriminator, mut data) = data.split_at(8);
+
+        if discriminator == ClaimedEvent::DISCRIMINATOR {
+            // The legacy layout is a prefix of the current one, so it is only tried second.
+            return match ClaimedEvent::deserialize(&mut &data[..]) {
+                Ok(event) => Ok(Some(Self::Claimed(event))),
+                Err(_) => LegacyClaimedEvent::deserialize(&mut data).map(|event| {
+                    Some(Self::LegacyClaimed {
+                        event,
+                        distributor: None,
+                    })
+                }),
+            };
+        }
+
+        macro_rules! decode {
+            ($($event:ident => $variant:ident),* $(,)?) => {
+                $(
+                    if discriminator == $event::DISCRIMINATOR {
+                        return $event::deserialize(&mut data).map(|e| Some(Self::$variant(e)));
+                    }
+                )*
+            };
+        }
+        decode!(
+            DistributorCreatedEvent => DistributorCreated,
+            DistributorMigratedEvent => DistributorMigrated,
+            RootUpdatedEvent => RootUpdated,
+            ClaimWindowUpdatedEvent => ClaimWindowUpdated,
+            ClaimPercentagesUpdatedEvent => ClaimPercentagesUpdated,
+            GracePeriodUpdatedEvent => GracePeriodUpdated,
+            ScreeningProgramUpdatedEvent => ScreeningProgramUpdated,
+            RateLimitsUpdatedEvent => RateLimitsUpdated,
+            RateLimitTrippedEvent => RateLimitTripped,
+            StakeClaimScalingUpdatedEvent => StakeClaimScalingUpdated,
+            AdminAuthUpdatedEvent => AdminAuthUpdated,
+            ClaimTargetAddedEvent => ClaimTargetAdded,
+            ClaimTargetRemovedEvent => ClaimTargetRemoved,
+            ClaimReboundEvent => ClaimRebound,
+            ClaimRevokedEvent => ClaimRevoked,
+            AdminClaimedAfterExpiryEvent => AdminClaimedAfterExpiry,
+            SweepRecipientUpdatedEvent => SweepRecipientUpdated,
+            SweptEvent => Swept,
+            ClaimDelegateSetEvent => ClaimDelegateSet,
+            ClaimDelegateRemovedEvent => ClaimDelegateRemoved,
+            ClaimStatusMigratedEvent => ClaimStatusMigrated,
+        );
+        Ok(None)
+    }
+
+    /// The distributor the event is about, `None` for a [Self::LegacyClaimed] that could not be
+    /// attributed and for the events of a claimant across distributors: claim delegates and claim
+    /// status migrations.
+    pub fn distributor(&self) -> Option<Pubkey> {
+        let distributor = match self {
+            Self::Claimed(e) => e.distributor,
+            Self::LegacyClaimed { distributor, .. } => return *distributor,
+            Self::DistributorCreated(e) => e.distributor,
+            Self::DistributorMigrated(e) => e.distributor,
+            Self::RootUpdated(e) => e.distributor,
+            Self::ClaimWindowUpdated(e) => e.distributor,
+            Self::ClaimPercentagesUpdated(e) => e.distributor,
+            Self::GracePeriodUpdated(e) => e.distributor,
+            Self::ScreeningProgramUpdated(e) => e.distributor,
+            Self::RateLimitsUpdated(e) => e.distributor,
+            Self::RateLimitTripped(e) => e.distributor,
+            Self::StakeClaimScalingUpdated(e) => e.distributor,
+            Self::AdminAuthUpdated(e) => e.distributor,
+            Self::ClaimTargetAdded(e) => e.distributor,
+            Self::ClaimTargetRemoved(e) => e.distributor,
+            Self::ClaimRebound(e) => e.distributor,
+            Self::ClaimRevoked(e) => e.distributor,
+            Self::AdminClaimedAfterExpiry(e) => e.distributor,
+            Self::SweepRecipientUpdated(e) => e.distributor,
+            Self::Swept(e) => e.distributor,
+            Self::ClaimDelegateSet(_)
+            | Self::ClaimDelegateRemoved(_)
+            | Self::ClaimStatusMigrated(_) => return None,
+        };
+        Some(distributor)
+    }
+}
+
+/// Events decoded from the log messages of a transaction.
+#[derive(Default)]
+pub struct ParsedLogs {
+    /// Events emitted by the program, in order.
+    pub events: Vec<DistributorEvent>,
+    /// Data the program logged that did not decode, e.g. events of a newer program version.
+    pub num_invalid: u64,
+}
+
+/// Events emitted by `program_id` in the log messages of a transaction.
+pub fn parse_logs(program_id: &Pubkey, logs: &[String]) -> ParsedLogs {
+    let program_id = program_id.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut parsed = ParsedLogs::default();
+
+    for log in logs {
+        let Some(log) = log.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = log.strip_prefix("data: ") {
+            if invocations.last() == Some(&program_id.as_str()) {
+                match base64::engine::general_purpose::STANDARD.decode(data) {
+                    Ok(data) => match DistributorEvent::decode(&data) {
+                        Ok(event) => parsed.events.extend(event),
+                        Err(_) => parsed.num_invalid += 1,
+                    },
+                    Err(_) => parsed.num_invalid += 1,
+                }
+            }
+            continue;
+        }
+
+        let mut words = log.split(' ');
+        match (words.next(), words.next()) {
+            (Some(program), Some("invoke")) => invocations.push(program),
+            (Some(_), Some("success" | "failed:")) => {
+                invocations.pop();
+            }
+            _ => {}
+        }
+    }
+
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+
+    fn data_log(event: &impl Event) -> String {
+        format!(
+            "Program data: {}",
+            base64::engine::general_purpose::STANDARD.encode(event.data())
+        )
+    }
+
+    fn claimed(distributor: Pubkey) -> ClaimedEvent {
+        ClaimedEvent {
+            root: [1; 32],
+            index: 3,
+            claimant: Pubkey::new_unique(),
+            claim_amount: 100,
+            distributor,
+            transfer_amount: 60,
+            forfeited_amount: 40,
+            destination: Pubkey::new_unique(),
+            staked: false,
+            stake_duration_epochs: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_logs() {
+        let program_id = merkle_distributor::ID;
+        let other_program = Pubkey::new_unique();
+        let distributor = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {program_id} invoke [1]"),
+            "Program log: Instruction: Claim".to_string(),
+            format!("Program {other_program} invoke [2]"),
+            // Logged by the inner program, not ours.
+            data_log(&claimed(Pubkey::new_unique())),
+            format!("Program {other_program} consumed 100 of 200 compute units"),
+            format!("Program {other_program} success"),
+            data_log(&claimed(distributor)),
+            "Program data: not base64".to_string(),
+            format!("Program {program_id} consumed 1000 of 200000 compute units"),
+            format!("Program {program_id} success"),
+        ];
+
+        let ParsedLogs {
+            events,
+            num_invalid,
+        } = parse_logs(&program_id, &logs);
+        assert_eq!(num_invalid, 1);
+        assert_eq!(events.len(), 1);
+        let DistributorEvent::Claimed(event) = &events[0] else {
+            panic!("expected a claim");
+        };
+        assert_eq!(event.distributor, distributor);
+        assert_eq!(event.index, 3);
+        assert_eq!(event.forfeited_amount, 40);
+    }
+
+    #[test]
+    fn test_decode_unknown() {
+        let mut data = claimed(Pubkey::new_unique()).data();
+        data[0] ^= 1;
+        assert!(DistributorEvent::decode(&data).unwrap().is_none());
+        assert!(DistributorEvent::decode(&data[..4]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decode_claimant_events() {
+        let event = ClaimDelegateSetEvent {
+            claimant: Pubkey::new_unique(),
+            delegate: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+        };
+        let Some(DistributorEvent::ClaimDelegateSet(decoded)) =
+            DistributorEvent::decode(&event.data()).unwrap()
+        else {
+            panic!("expected a delegate");
+        };
+        assert_eq!(decoded.delegate, event.delegate);
+        assert_eq!(
+            DistributorEvent::ClaimDelegateSet(decoded).distributor(),
+            None
+        );
+
+        let event = ClaimStatusMigratedEvent {
+            claim_status: Pubkey::new_unique(),
+            claimant: Pubkey::new_unique(),
+        };
+        assert!(matches!(
+            DistributorEvent::decode(&event.data()).unwrap(),
+            Some(DistributorEvent::ClaimStatusMigrated(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_legacy_claimed() {
+        let event = claimed(Pubkey::new_unique());
+        // Discriminator, root, index, claimant and claim amount.
+        let data = event.data()[..8 + 80].to_vec();
+        let Some(DistributorEvent::LegacyClaimed {
+            event: legacy,
+            distributor,
+        }) = DistributorEvent::decode(&data).unwrap()
+        else {
+            panic!("expected a legacy claim");
+        };
+        assert_eq!(legacy.claimant, event.claimant);
+        assert_eq!(legacy.claim_amount, 100);
+        assert_eq!(distributor, None);
+
+        assert!(DistributorEvent::decode(&data[..8 + 40]).is_err());
+    }
+}
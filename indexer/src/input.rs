@@ -0,0 +1,286 @@
+//! Reading transactions from JSON.
+//!
+//! Transactions are `getTransaction` results of the JSON RPC, e.g. as saved by
+//! `curl <rpc> -d '{"method": "getTransaction", ...}'`. A file holds a single transaction, a JSON
+//! array of them or one per line (JSON lines), each either bare or still wrapped in its RPC
+//! response. The slot, block time, signature and log messages work with any transaction encoding;
+//! the instructions, only needed to attribute claims logged before `ClaimedEvent` named its
+//! distributor, are read from the `json` and `jsonParsed` encodings.
+//!
+//! A directory holding a `rocksdb` directory is a validator ledger instead, read with the
+//! `blockstore` feature, see [crate::blockstore]. Without it, serve the ledger over RPC first, e.g.
+//! with `solana-test-validator --ledger <dir>`, and fetch its transactions from there.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+#[cfg(feature = "blockstore")]
+use crate::blockstore::read_blockstore;
+
+/// A transaction, reduced to what the indexer needs.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    #[serde(default)]
+    pub transaction: Value,
+    pub meta: Option<Meta>,
+}
+
+/// Status and logs of a [Transaction].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    pub err: Option<Value>,
+    pub log_messages: Option<Vec<String>>,
+    #[serde(default)]
+    pub loaded_addresses: Option<LoadedAddresses>,
+}
+
+/// Accounts a versioned [Transaction] loaded from address lookup tables.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct LoadedAddresses {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>,
+}
+
+impl Transaction {
+    /// First signature of the transaction, if it was fetched in a JSON encoding.
+    pub fn signature(&self) -> Option<&str> {
+        self.transaction.get("signatures")?.get(0)?.as_str()
+    }
+
+    /// Whether the transaction succeeded; failed transactions changed no state.
+    pub fn succeeded(&self) -> bool {
+        self.meta.as_ref().is_some_and(|meta| meta.err.is_none())
+    }
+
+    /// Accounts of the top-level instructions to `program_id`, in order. Empty if the transaction
+    /// was not fetched in a JSON encoding.
+    pub fn instruction_accounts(&self, program_id: &str) -> Vec<Vec<&str>> {
+        let Some(message) = self.transaction.get("message") else {
+            return Vec::new();
+        };
+        let mut keys: Vec<&str> = message
+            .get("accountKeys")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            // `jsonParsed` lists keys as objects.
+            .filter_map(|key| key.as_str().or_else(|| key.get("pubkey")?.as_str()))
+            .collect();
+        if let Some(loaded) = self.meta.as_ref().and_then(|m| m.loaded_addresses.as_ref()) {
+            keys.extend(loaded.writable.iter().map(String::as_str));
+            keys.extend(loaded.readonly.iter().map(String::as_str));
+        }
+        // `json` refers to keys by index, `jsonParsed` by value.
+        let key = |value| account_key(&keys, value);
+
+        message
+            .get("instructions")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|ix| {
+                ix.get("programIdIndex")
+                    .or_else(|| ix.get("programId"))
+                    .and_then(key)
+                    == Some(program_id)
+            })
+            .map(|ix| {
+                ix.get("accounts")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(key)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Log messages of the transaction, empty if they were not recorded.
+    pub fn logs(&self) -> &[String] {
+        self.meta
+            .as_ref()
+            .and_then(|meta| meta.log_messages.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Reads the transactions of `paths`, descending into directories, ordered by slot.
+pub fn read_transactions(paths: &[PathBuf]) -> io::Result<Vec<Transaction>> {
+    let mut transactions = Vec::new();
+    for path in paths {
+        read_path(path, &mut transactions)?;
+    }
+    // Stable, so transactions of a slot keep the order they were read in.
+    transactions.sort_by_key(|tx| tx.slot);
+    Ok(transactions)
+}
+
+fn read_path(path: &Path, transactions: &mut Vec<Transaction>) -> io::Result<()> {
+    if path.join("rocksdb").is_dir() {
+        return read_blockstore(path, transactions);
+    }
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir()
+                || entry
+                    .extension()
+                    .is_some_and(|ext| ext == "json" || ext == "jsonl")
+            {
+                read_path(&entry, transactions)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let contents = fs::read_to_string(path)?;
+    let values = match serde_json::from_str(&contents) {
+        Ok(Value::Array(values)) => values,
+        Ok(value) => vec![value],
+        Err(_) => contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|err| invalid_data(path, err))?,
+    };
+    for value in values {
+        transactions.push(parse_transaction(value).map_err(|err| invalid_data(path, err))?);
+    }
+    Ok(())
+}
+
+/// Parses a transaction, unwrapping its RPC response if needed.
+pub fn parse_transaction(mut value: Value) -> serde_json::Result<Transaction> {
+    if let Some(result) = value.get_mut("result") {
+        value = result.take();
+    }
+    serde_json::from_value(value)
+}
+
+fn account_key<'a>(keys: &[&'a str], value: &'a Value) -> Option<&'a str> {
+    match value.as_u64() {
+        Some(index) => keys.get(index as usize).copied(),
+        None => value.as_str(),
+    }
+}
+
+#[cfg(not(feature = "blockstore"))]
+fn read_blockstore(path: &Path, _transactions: &mut Vec<Transaction>) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "cannot read ledger {}: built without the blockstore feature",
+            path.display()
+        ),
+    ))
+}
+
+fn invalid_data(path: &Path, err: serde_json::Error) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {err}", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_transaction() {
+        let tx = parse_transaction(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "slot": 42,
+                "blockTime": 1700000000,
+                "transaction": { "signatures": ["sig"], "message": {} },
+                "meta": { "err": null, "logMessages": ["Program log: hi"] },
+            },
+        }))
+        .unwrap();
+        assert_eq!(tx.slot, 42);
+        assert_eq!(tx.block_time, Some(1700000000));
+        assert_eq!(tx.signature(), Some("sig"));
+        assert!(tx.succeeded());
+        assert_eq!(tx.logs(), ["Program log: hi"]);
+        assert!(tx.instruction_accounts("program").is_empty());
+
+        // Base64 encoded, failed and without logs.
+        let tx = parse_transaction(json!({
+            "slot": 43,
+            "blockTime": null,
+            "transaction": ["AAAA", "base64"],
+            "meta": { "err": { "InstructionError": [0, "Custom"] } },
+        }))
+        .unwrap();
+        assert_eq!(tx.signature(), None);
+        assert!(!tx.succeeded());
+        assert!(tx.logs().is_empty());
+        assert!(tx.instruction_accounts("program").is_empty());
+    }
+
+    #[test]
+    fn test_instruction_accounts() {
+        let tx = parse_transaction(json!({
+            "slot": 44,
+            "transaction": {
+                "signatures": ["sig"],
+                "message": {
+                    "accountKeys": ["payer", "distributor", "program", "other"],
+                    "instructions": [
+                        { "programIdIndex": 3, "accounts": [0], "data": "" },
+                        { "programIdIndex": 2, "accounts": [1, 0, 4], "data": "" },
+                    ],
+                },
+            },
+            "meta": {
+                "err": null,
+                "loadedAddresses": { "writable": ["vault"], "readonly": [] },
+            },
+        }))
+        .unwrap();
+        assert_eq!(
+            tx.instruction_accounts("program"),
+            [["distributor", "payer", "vault"]]
+        );
+
+        let tx = parse_transaction(json!({
+            "slot": 45,
+            "transaction": {
+                "message": {
+                    "accountKeys": [{ "pubkey": "payer", "signer": true }],
+                    "instructions": [
+                        { "programId": "program", "accounts": ["distributor"], "data": "" },
+                    ],
+                },
+            },
+            "meta": null,
+        }))
+        .unwrap();
+        assert_eq!(tx.instruction_accounts("program"), [["distributor"]]);
+    }
+
+    #[cfg(not(feature = "blockstore"))]
+    #[test]
+    fn test_read_ledger_without_blockstore() {
+        let ledger = std::env::temp_dir().join(format!("indexer-ledger-{}", std::process::id()));
+        fs::create_dir_all(ledger.join("rocksdb")).unwrap();
+        let err = read_transactions(std::slice::from_ref(&ledger)).unwrap_err();
+        fs::remove_dir_all(&ledger).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+}
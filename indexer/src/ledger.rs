@@ -0,0 +1,462 @@
+//! Per-distributor and per-claimant state rebuilt from events.
+
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::events::{parse_logs, DistributorEvent};
+use crate::input::Transaction;
+
+/// Where an event was emitted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Origin {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub signature: Option<String>,
+}
+
+/// A single claim, from its [merkle_distributor::ClaimedEvent].
+///
+/// The fields after `claim_amount` are `None` for claims logged as a
+/// [crate::events::LegacyClaimedEvent].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Claim {
+    pub origin: Origin,
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    pub root: [u8; 32],
+    /// Amount counted against the allocation.
+    pub claim_amount: u64,
+    /// Amount that left the vault.
+    pub transfer_amount: Option<u64>,
+    /// Amount given up to the early claim ramp.
+    pub forfeited_amount: Option<u64>,
+    pub destination: Option<Pubkey>,
+    pub staked: Option<bool>,
+    pub stake_duration_epochs: Option<u32>,
+}
+
+/// Claims of a claimant from a distributor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClaimantState {
+    pub index: u64,
+    pub claimed_amount: u64,
+    pub transferred_amount: u64,
+    pub forfeited_amount: u64,
+    pub staked_amount: u64,
+    pub num_claims: u64,
+    pub first_claimed_at: Option<i64>,
+    pub last_claimed_at: Option<i64>,
+    /// Set once [merkle_distributor::merkle_distributor::revoke_claim] revoked the allocation.
+    pub revoked_at: Option<i64>,
+}
+
+/// State of a distributor.
+///
+/// Fields set by [merkle_distributor::DistributorCreatedEvent] are `None` when the creation is not
+/// part of the indexed transactions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DistributorState {
+    pub mint: Option<Pubkey>,
+    pub admin_auth: Option<Pubkey>,
+    pub root: Option<[u8; 32]>,
+    pub max_total_claim: Option<u64>,
+    pub max_num_nodes: Option<u64>,
+    pub claim_start_ts: Option<u64>,
+    pub claim_end_ts: Option<u64>,
+    /// Whether the distributor hands out lamports instead of tokens of `mint`.
+    pub native: Option<bool>,
+    pub created_at: Option<i64>,
+
+    pub claimed_amount: u64,
+    pub transferred_amount: u64,
+    pub forfeited_amount: u64,
+    pub staked_amount: u64,
+    pub num_claims: u64,
+    /// Claims logged as a [crate::events::LegacyClaimedEvent], counted in `claimed_amount` and
+    /// `num_claims` but not in the transferred, forfeited and staked amounts.
+    pub num_legacy_claims: u64,
+    /// Unclaimed amount of revoked allocations, no longer claimable.
+    pub revoked_amount: u64,
+    /// Number of claims skipped for exceeding a rate limit.
+    pub num_rate_limited: u64,
+    /// Amount swept out by [merkle_distributor::merkle_distributor::admin_claim_after_expiry] and
+    /// the sweep instructions.
+    pub swept_amount: u64,
+    pub swept_at: Option<i64>,
+
+    pub claimants: BTreeMap<Pubkey, ClaimantState>,
+}
+
+impl DistributorState {
+    /// Amount of the allocation not claimed or revoked yet, if the maximum claim is known.
+    pub fn remaining_unclaimed(&self) -> Option<u64> {
+        self.max_total_claim.map(|max| {
+            max.saturating_sub(self.claimed_amount)
+                .saturating_sub(self.revoked_amount)
+        })
+    }
+}
+
+/// State of every distributor seen, and the history of claims.
+#[derive(Clone, Debug, Default)]
+pub struct Ledger {
+    pub distributors: BTreeMap<Pubkey, DistributorState>,
+    pub claims: Vec<Claim>,
+    /// Data the program logged that did not decode as any of its events.
+    pub num_invalid_events: u64,
+    /// [crate::events::LegacyClaimedEvent]s whose distributor could not be told from their
+    /// transaction.
+    pub num_unattributed_events: u64,
+}
+
+impl Ledger {
+    /// Replays the events `program_id` emitted in `transactions`, which must be in order.
+    pub fn from_transactions(program_id: &Pubkey, transactions: &[Transaction]) -> Self {
+        let mut ledger = Self::default();
+        for tx in transactions.iter().filter(|tx| tx.succeeded()) {
+            let origin = Origin {
+                slot: tx.slot,
+                block_time: tx.block_time,
+                signature: tx.signature().map(str::to_string),
+            };
+            let parsed = parse_logs(program_id, tx.logs());
+            ledger.num_invalid_events += parsed.num_invalid;
+            for mut event in parsed.events {
+                if let DistributorEvent::LegacyClaimed { distributor, .. } = &mut event {
+                    *distributor = legacy_claim_distributor(program_id, tx);
+                }
+                ledger.apply(&origin, event);
+            }
+        }
+        ledger
+    }
+
+    /// Applies a single event.
+    pub fn apply(&mut self, origin: &Origin, event: DistributorEvent) {
+        let Some(key) = event.distributor() else {
+            // Claim delegates and claim status migrations change no distributor's totals.
+            if let DistributorEvent::LegacyClaimed { .. } = event {
+                self.num_unattributed_events += 1;
+            }
+            return;
+        };
+        let distributor = self.distributors.entry(key).or_default();
+        let at = origin.block_time;
+
+        match event {
+            DistributorEvent::Claimed(e) => {
+                distributor.claimed_amount += e.claim_amount;
+                distributor.transferred_amount += e.transfer_amount;
+                distributor.forfeited_amount += e.forfeited_amount;
+                distributor.num_claims += 1;
+
+                let claimant = distributor.claimants.entry(e.claimant).or_default();
+                claimant.index = e.index;
+                claimant.claimed_amount += e.claim_amount;
+                claimant.transferred_amount += e.transfer_amount;
+                claimant.forfeited_amount += e.forfeited_amount;
+                claimant.num_claims += 1;
+                claimant.first_claimed_at = claimant.first_claimed_at.or(at);
+                claimant.last_claimed_at = at.or(claimant.last_claimed_at);
+                if e.staked {
+                    distributor.staked_amount += e.transfer_amount;
+                    claimant.staked_amount += e.transfer_amount;
+                }
+
+                self.claims.push(Claim {
+                    origin: origin.clone(),
+                    distributor: e.distributor,
+                    claimant: e.claimant,
+                    index: e.index,
+                    root: e.root,
+                    claim_amount: e.claim_amount,
+                    transfer_amount: Some(e.transfer_amount),
+                    forfeited_amount: Some(e.forfeited_amount),
+                    destination: Some(e.destination),
+                    staked: Some(e.staked),
+                    stake_duration_epochs: Some(e.stake_duration_epochs),
+                });
+            }
+            DistributorEvent::LegacyClaimed { event: e, .. } => {
+                distributor.claimed_amount += e.claim_amount;
+                distributor.num_claims += 1;
+                distributor.num_legacy_claims += 1;
+
+                let claimant = distributor.claimants.entry(e.claimant).or_default();
+                claimant.index = e.index;
+                claimant.claimed_amount += e.claim_amount;
+                claimant.num_claims += 1;
+                claimant.first_claimed_at = claimant.first_claimed_at.or(at);
+                claimant.last_claimed_at = at.or(claimant.last_claimed_at);
+
+                self.claims.push(Claim {
+                    origin: origin.clone(),
+                    distributor: key,
+                    claimant: e.claimant,
+                    index: e.index,
+                    root: e.root,
+                    claim_amount: e.claim_amount,
+                    transfer_amount: None,
+                    forfeited_amount: None,
+                    destination: None,
+                    staked: None,
+                    stake_duration_epochs: None,
+                });
+            }
+            DistributorEvent::DistributorCreated(e) => {
+                distributor.mint = Some(e.mint);
+                distributor.admin_auth = Some(e.admin_auth);
+                distributor.root = Some(e.root);
+                distributor.max_total_claim = Some(e.max_total_claim);
+                distributor.max_num_nodes = Some(e.max_num_nodes);
+                distributor.claim_start_ts = Some(e.claim_start_ts);
+                distributor.claim_end_ts = Some(e.claim_end_ts);
+                distributor.native = Some(e.native);
+                distributor.created_at = at;
+            }
+            DistributorEvent::RootUpdated(e) => {
+                distributor.root = Some(e.root);
+                distributor.max_total_claim = Some(e.max_total_claim);
+                distributor.max_num_nodes = Some(e.max_num_nodes);
+            }
+            DistributorEvent::ClaimWindowUpdated(e) => {
+                distributor.claim_start_ts = Some(e.claim_start_ts);
+                distributor.claim_end_ts = Some(e.claim_end_ts);
+            }
+            DistributorEvent::AdminAuthUpdated(e) => {
+                distributor.admin_auth = Some(e.new_admin_auth);
+            }
+            DistributorEvent::AdminClaimedAfterExpiry(e) => {
+                distributor.swept_amount += e.amount;
+                distributor.swept_at = at;
+            }
+            DistributorEvent::ClaimRevoked(e) => {
+                distributor.revoked_amount += e.revoked_amount;
+                let claimant = distributor.claimants.entry(e.claimant).or_default();
+                claimant.index = e.index;
+                claimant.revoked_at = at;
+            }
+            DistributorEvent::RateLimitTripped(_) => {
+                distributor.num_rate_limited += 1;
+            }
+            DistributorEvent::Swept(e) => {
+                distributor.swept_amount += e.amount;
+                distributor.swept_at = at;
+            }
+            DistributorEvent::DistributorMigrated(_)
+            | DistributorEvent::ClaimPercentagesUpdated(_)
+            | DistributorEvent::GracePeriodUpdated(_)
+            | DistributorEvent::ScreeningProgramUpdated(_)
+            | DistributorEvent::RateLimitsUpdated(_)
+            | DistributorEvent::StakeClaimScalingUpdated(_)
+            | DistributorEvent::ClaimTargetAdded(_)
+            | DistributorEvent::ClaimTargetRemoved(_)
+            // Claims of rebound leaves are still logged under the original claimant.
+            | DistributorEvent::ClaimRebound(_)
+            | DistributorEvent::SweepRecipientUpdated(_)
+            | DistributorEvent::ClaimDelegateSet(_)
+            | DistributorEvent::ClaimDelegateRemoved(_)
+            | DistributorEvent::ClaimStatusMigrated(_) => {}
+        }
+    }
+}
+
+/// The distributor of a [crate::events::LegacyClaimedEvent], the first account of every
+/// instruction of `program_id` in `tx`. `None` if they disagree or the instructions were not
+/// recorded.
+fn legacy_claim_distributor(program_id: &Pubkey, tx: &Transaction) -> Option<Pubkey> {
+    let instructions = tx.instruction_accounts(&program_id.to_string());
+    let mut distributors = instructions.iter().map(|accounts| accounts.first());
+    let first = distributors.next()??;
+    if distributors.all(|distributor| distributor == Some(first)) {
+        first.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merkle_distributor::claim::LeafVersion;
+    use merkle_distributor::merkle_proof::{HashFunction, HashScheme};
+    use merkle_distributor::{
+        AdminClaimedAfterExpiryEvent, ClaimRevokedEvent, ClaimedEvent, DistributorCreatedEvent,
+        StakeClaimScaling, SweptEvent,
+    };
+
+    fn origin(block_time: i64) -> Origin {
+        Origin {
+            slot: block_time as u64,
+            block_time: Some(block_time),
+            signature: None,
+        }
+    }
+
+    fn claimed(
+        distributor: Pubkey,
+        claimant: Pubkey,
+        claim_amount: u64,
+        transfer_amount: u64,
+        staked: bool,
+    ) -> DistributorEvent {
+        DistributorEvent::Claimed(ClaimedEvent {
+            root: [1; 32],
+            index: 0,
+            claimant,
+            claim_amount,
+            distributor,
+            transfer_amount,
+            forfeited_amount: claim_amount - transfer_amount,
+            destination: Pubkey::new_unique(),
+            staked,
+            stake_duration_epochs: if staked { 90 } else { 0 },
+        })
+    }
+
+    #[test]
+    fn test_apply() {
+        let distributor = Pubkey::new_unique();
+        let [alice, bob, carol] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let mut ledger = Ledger::default();
+
+        // Claims before the creation was indexed leave the allocation unknown.
+        ledger.apply(&origin(10), claimed(distributor, alice, 100, 60, false));
+        assert_eq!(
+            ledger.distributors[&distributor].remaining_unclaimed(),
+            None
+        );
+
+        ledger.apply(
+            &origin(5),
+            DistributorEvent::DistributorCreated(DistributorCreatedEvent {
+                distributor,
+                base: Pubkey::new_unique(),
+                admin_auth: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+                root: [1; 32],
+                max_total_claim: 1_000,
+                max_num_nodes: 2,
+                claim_start_ts: 0,
+                claim_end_ts: 100,
+                stake_claim_only: false,
+                immediate_claim_percentage: 0,
+                later_claim_offset_seconds: 0,
+                stake_claim_scaling: StakeClaimScaling::Full,
+                stake_immediate_claim_percentage: 0,
+                hash_scheme: HashScheme::Legacy,
+                leaf_version: LeafVersion::V0,
+                hash_function: HashFunction::Keccak,
+                native: false,
+            }),
+        );
+        ledger.apply(&origin(20), claimed(distributor, alice, 50, 50, false));
+        ledger.apply(&origin(30), claimed(distributor, bob, 200, 200, true));
+        ledger.apply(
+            &origin(40),
+            DistributorEvent::Swept(SweptEvent {
+                distributor,
+                to: Pubkey::new_unique(),
+                amount: 40,
+                permissionless: false,
+            }),
+        );
+        ledger.apply(
+            &origin(200),
+            DistributorEvent::AdminClaimedAfterExpiry(AdminClaimedAfterExpiryEvent {
+                distributor,
+                to: Pubkey::new_unique(),
+                amount: 650,
+            }),
+        );
+        ledger.apply(
+            &origin(50),
+            DistributorEvent::ClaimRevoked(ClaimRevokedEvent {
+                distributor,
+                claimant: carol,
+                index: 2,
+                revoked_amount: 100,
+                returned: true,
+            }),
+        );
+
+        let state = &ledger.distributors[&distributor];
+        assert_eq!(state.claimed_amount, 350);
+        assert_eq!(state.transferred_amount, 310);
+        assert_eq!(state.forfeited_amount, 40);
+        assert_eq!(state.staked_amount, 200);
+        assert_eq!(state.num_claims, 3);
+        assert_eq!(state.revoked_amount, 100);
+        assert_eq!(state.remaining_unclaimed(), Some(550));
+        assert_eq!(state.swept_amount, 690);
+        assert_eq!(state.swept_at, Some(200));
+
+        let alice = &state.claimants[&alice];
+        assert_eq!(alice.claimed_amount, 150);
+        assert_eq!(alice.transferred_amount, 110);
+        assert_eq!(alice.num_claims, 2);
+        assert_eq!(alice.first_claimed_at, Some(10));
+        assert_eq!(alice.last_claimed_at, Some(20));
+        assert_eq!(state.claimants[&bob].staked_amount, 200);
+        assert_eq!(state.claimants[&carol].revoked_at, Some(50));
+        assert_eq!(ledger.claims.len(), 3);
+    }
+
+    #[test]
+    fn test_legacy_claims() {
+        use anchor_lang::Event;
+        use base64::Engine;
+
+        let program_id = merkle_distributor::ID;
+        let distributor = Pubkey::new_unique();
+        let claimant = Pubkey::new_unique();
+        let DistributorEvent::Claimed(event) = claimed(distributor, claimant, 100, 60, false)
+        else {
+            unreachable!()
+        };
+        // Logged before the event named its distributor.
+        let legacy = base64::engine::general_purpose::STANDARD.encode(&event.data()[..8 + 80]);
+        let tx = |instructions: serde_json::Value| {
+            crate::input::parse_transaction(serde_json::json!({
+                "slot": 1,
+                "blockTime": 10,
+                "transaction": {
+                    "message": {
+                        "accountKeys": [claimant.to_string(), program_id.to_string()],
+                        "instructions": instructions,
+                    },
+                },
+                "meta": {
+                    "err": null,
+                    "logMessages": [
+                        format!("Program {program_id} invoke [1]"),
+                        format!("Program data: {legacy}"),
+                        "Program data: not base64".to_string(),
+                        format!("Program {program_id} success"),
+                    ],
+                },
+            }))
+            .unwrap()
+        };
+        let attributed = tx(serde_json::json!([
+            { "programId": program_id.to_string(), "accounts": [distributor.to_string(), 0] },
+        ]));
+        let unattributed = tx(serde_json::json!([]));
+
+        let ledger = Ledger::from_transactions(&program_id, &[attributed, unattributed]);
+        assert_eq!(ledger.num_invalid_events, 2);
+        assert_eq!(ledger.num_unattributed_events, 1);
+        let state = &ledger.distributors[&distributor];
+        assert_eq!(state.claimed_amount, 100);
+        assert_eq!(state.transferred_amount, 0);
+        assert_eq!(state.num_legacy_claims, 1);
+        assert_eq!(state.claimants[&claimant].last_claimed_at, Some(10));
+        assert_eq!(ledger.claims[0].transfer_amount, None);
+    }
+}
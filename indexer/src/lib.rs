@@ -0,0 +1,12 @@
+//! Rebuilds the state of merkle distributors from the events the program logs.
+//!
+//! Transactions are read from JSON or a validator ledger ([input]), the events of the program are decoded from their
+//! log messages ([events]) and replayed into a [ledger::Ledger] of per-distributor and
+//! per-claimant state, which [report] writes out as CSV or SQLite.
+
+#[cfg(feature = "blockstore")]
+pub mod blockstore;
+pub mod events;
+pub mod input;
+pub mod ledger;
+pub mod report;
@@ -0,0 +1,132 @@
+//! Rebuilds merkle distributor state from transactions and writes CSV or SQLite reports.
+//!
+//! ```sh
+//! cargo run --release --manifest-path indexer/Cargo.toml -- \
+//!     --csv reports/ --sqlite reports/distributor.db transactions/
+//! ```
+//!
+//! Inputs are files or directories of transactions, or validator ledgers, see
+//! [merkle_distributor_indexer::input].
+//! Prints a summary of every distributor.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use merkle_distributor_indexer::input::read_transactions;
+use merkle_distributor_indexer::ledger::Ledger;
+use merkle_distributor_indexer::report;
+
+const USAGE: &str = "\
+usage: merkle-distributor-indexer [options] <input>...
+
+options:
+    --program-id <id>   program to index, defaults to the merkle distributor
+    --csv <dir>         write distributors.csv, claimants.csv and claims.csv to <dir>
+    --sqlite <file>     write the distributors, claimants and claims tables to <file>";
+
+struct Args {
+    program_id: Pubkey,
+    csv: Option<PathBuf>,
+    sqlite: Option<PathBuf>,
+    inputs: Vec<PathBuf>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            program_id: merkle_distributor::ID,
+            csv: None,
+            sqlite: None,
+            inputs: Vec::new(),
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value of {arg}"));
+            match arg.as_str() {
+                "--program-id" => {
+                    let value = value()?;
+                    parsed.program_id = Pubkey::from_str(&value)
+                        .map_err(|err| format!("invalid program id {value}: {err}"))?;
+                }
+                "--csv" => parsed.csv = Some(value()?.into()),
+                "--sqlite" => parsed.sqlite = Some(value()?.into()),
+                _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+                _ => parsed.inputs.push(arg.into()),
+            }
+        }
+        if parsed.inputs.is_empty() {
+            return Err("no inputs".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let transactions = match read_transactions(&args.inputs) {
+        Ok(transactions) => transactions,
+        Err(err) => {
+            eprintln!("reading transactions: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let ledger = Ledger::from_transactions(&args.program_id, &transactions);
+
+    println!(
+        "{} transactions, {} distributors, {} claims",
+        transactions.len(),
+        ledger.distributors.len(),
+        ledger.claims.len()
+    );
+    if ledger.num_invalid_events > 0 || ledger.num_unattributed_events > 0 {
+        eprintln!(
+            "skipped {} events that did not decode and {} legacy claims of an unknown distributor",
+            ledger.num_invalid_events, ledger.num_unattributed_events
+        );
+    }
+    for (key, distributor) in &ledger.distributors {
+        println!(
+            "{key}: {} claimants, {} claimed, {} transferred, {} forfeited, {} unclaimed",
+            distributor.claimants.len(),
+            distributor.claimed_amount,
+            distributor.transferred_amount,
+            distributor.forfeited_amount,
+            distributor
+                .remaining_unclaimed()
+                .map_or("unknown".to_string(), |amount| amount.to_string()),
+        );
+    }
+
+    let tables = report::tables(&ledger);
+    if let Some(dir) = &args.csv {
+        if let Err(err) = report::write_csv_dir(dir, &tables) {
+            eprintln!("writing {}: {err}", dir.display());
+            return ExitCode::FAILURE;
+        }
+    }
+    if let Some(path) = &args.sqlite {
+        #[cfg(feature = "sqlite")]
+        if let Err(err) = report::write_sqlite(path, &tables) {
+            eprintln!("writing {}: {err}", path.display());
+            return ExitCode::FAILURE;
+        }
+        #[cfg(not(feature = "sqlite"))]
+        {
+            eprintln!(
+                "cannot write {}: built without the sqlite feature",
+                path.display()
+            );
+            return ExitCode::FAILURE;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
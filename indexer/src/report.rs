@@ -0,0 +1,335 @@
+//! CSV and SQLite reports of a [Ledger].
+//!
+//! A report has three tables: `distributors` and `claimants` with the rebuilt state, and `claims`
+//! with every claim in order. Amounts are in the mint's base units.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::ledger::Ledger;
+
+/// A cell of a [Table].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    Integer(i128),
+    Text(String),
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// A table of a report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub name: &'static str,
+    pub columns: &'static [&'static str],
+    pub rows: Vec<Vec<Value>>,
+}
+
+fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The tables of the report of `ledger`.
+pub fn tables(ledger: &Ledger) -> Vec<Table> {
+    let distributors = Table {
+        name: "distributors",
+        columns: &[
+            "distributor",
+            "mint",
+            "admin_auth",
+            "root",
+            "max_total_claim",
+            "max_num_nodes",
+            "claim_start_ts",
+            "claim_end_ts",
+            "native",
+            "created_at",
+            "num_claimants",
+            "num_claims",
+            "num_legacy_claims",
+            "claimed_amount",
+            "transferred_amount",
+            "forfeited_amount",
+            "staked_amount",
+            "revoked_amount",
+            "num_rate_limited",
+            "remaining_unclaimed",
+            "swept_amount",
+            "swept_at",
+        ],
+        rows: ledger
+            .distributors
+            .iter()
+            .map(|(key, d)| {
+                vec![
+                    key.to_string().into(),
+                    d.mint.map(|mint| mint.to_string()).into(),
+                    d.admin_auth.map(|admin| admin.to_string()).into(),
+                    d.root.as_ref().map(hex).into(),
+                    d.max_total_claim.into(),
+                    d.max_num_nodes.into(),
+                    d.claim_start_ts.into(),
+                    d.claim_end_ts.into(),
+                    d.native.into(),
+                    d.created_at.into(),
+                    (d.claimants.len() as u64).into(),
+                    d.num_claims.into(),
+                    d.num_legacy_claims.into(),
+                    d.claimed_amount.into(),
+                    d.transferred_amount.into(),
+                    d.forfeited_amount.into(),
+                    d.staked_amount.into(),
+                    d.revoked_amount.into(),
+                    d.num_rate_limited.into(),
+                    d.remaining_unclaimed().into(),
+                    d.swept_amount.into(),
+                    d.swept_at.into(),
+                ]
+            })
+            .collect(),
+    };
+
+    let claimants = Table {
+        name: "claimants",
+        columns: &[
+            "distributor",
+            "claimant",
+            "leaf_index",
+            "num_claims",
+            "claimed_amount",
+            "transferred_amount",
+            "forfeited_amount",
+            "staked_amount",
+            "first_claimed_at",
+            "last_claimed_at",
+            "revoked_at",
+        ],
+        rows: ledger
+            .distributors
+            .iter()
+            .flat_map(|(key, d)| {
+                d.claimants.iter().map(move |(claimant, c)| {
+                    vec![
+                        key.to_string().into(),
+                        claimant.to_string().into(),
+                        c.index.into(),
+                        c.num_claims.into(),
+                        c.claimed_amount.into(),
+                        c.transferred_amount.into(),
+                        c.forfeited_amount.into(),
+                        c.staked_amount.into(),
+                        c.first_claimed_at.into(),
+                        c.last_claimed_at.into(),
+                        c.revoked_at.into(),
+                    ]
+                })
+            })
+            .collect(),
+    };
+
+    let claims = Table {
+        name: "claims",
+        columns: &[
+            "slot",
+            "block_time",
+            "signature",
+            "distributor",
+            "claimant",
+            "leaf_index",
+            "root",
+            "claim_amount",
+            "transfer_amount",
+            "forfeited_amount",
+            "destination",
+            "staked",
+            "stake_duration_epochs",
+        ],
+        rows: ledger
+            .claims
+            .iter()
+            .map(|c| {
+                vec![
+                    c.origin.slot.into(),
+                    c.origin.block_time.into(),
+                    c.origin.signature.clone().into(),
+                    c.distributor.to_string().into(),
+                    c.claimant.to_string().into(),
+                    c.index.into(),
+                    hex(&c.root).into(),
+                    c.claim_amount.into(),
+                    c.transfer_amount.into(),
+                    c.forfeited_amount.into(),
+                    c.destination.map(|d| d.to_string()).into(),
+                    c.staked.into(),
+                    c.stake_duration_epochs.map(u64::from).into(),
+                ]
+            })
+            .collect(),
+    };
+
+    vec![distributors, claimants, claims]
+}
+
+/// Writes `table` as CSV, with a header row.
+pub fn write_csv(writer: &mut impl Write, table: &Table) -> io::Result<()> {
+    let mut write_row = |cells: Vec<String>| writeln!(writer, "{}", cells.join(","));
+
+    write_row(
+        table
+            .columns
+            .iter()
+            .map(|column| column.to_string())
+            .collect(),
+    )?;
+    for row in &table.rows {
+        write_row(
+            row.iter()
+                .map(|value| match value {
+                    Value::Null => String::new(),
+                    Value::Integer(value) => value.to_string(),
+                    Value::Text(text) if text.contains([',', '"', '\n', '\r']) => {
+                        format!("\"{}\"", text.replace('"', "\"\""))
+                    }
+                    Value::Text(text) => text.clone(),
+                })
+                .collect(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes each table to `<dir>/<table>.csv`, creating `dir` if needed.
+pub fn write_csv_dir(dir: &Path, tables: &[Table]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for table in tables {
+        let file = fs::File::create(dir.join(format!("{}.csv", table.name)))?;
+        let mut writer = io::BufWriter::new(file);
+        write_csv(&mut writer, table)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+impl rusqlite::ToSql for Value {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        use rusqlite::types::{Null, ToSqlOutput};
+
+        Ok(match self {
+            Value::Null => ToSqlOutput::from(Null),
+            // SQLite integers are 64-bit; larger amounts are kept exact as text.
+            Value::Integer(value) => match i64::try_from(*value) {
+                Ok(value) => ToSqlOutput::from(value),
+                Err(_) => ToSqlOutput::from(value.to_string()),
+            },
+            Value::Text(text) => ToSqlOutput::from(text.as_str()),
+        })
+    }
+}
+
+/// Writes the tables to the SQLite database at `path`, replacing tables of the same name.
+#[cfg(feature = "sqlite")]
+pub fn write_sqlite(path: &Path, tables: &[Table]) -> rusqlite::Result<()> {
+    let mut conn = rusqlite::Connection::open(path)?;
+    let tx = conn.transaction()?;
+    for table in tables {
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", table.name), [])?;
+        tx.execute(
+            &format!("CREATE TABLE {} ({})", table.name, table.columns.join(", ")),
+            [],
+        )?;
+        let mut insert = tx.prepare(&format!(
+            "INSERT INTO {} VALUES ({})",
+            table.name,
+            vec!["?"; table.columns.len()].join(", ")
+        ))?;
+        for row in &table.rows {
+            insert.execute(rusqlite::params_from_iter(row))?;
+        }
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_csv() {
+        let table = Table {
+            name: "test",
+            columns: &["a", "b", "c"],
+            rows: vec![
+                vec![Value::from(1u64), Value::Null, Value::from(true)],
+                vec![
+                    Value::from(u64::MAX),
+                    Value::from("x,\"y\"".to_string()),
+                    Value::from(-1i64),
+                ],
+            ],
+        };
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &table).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "a,b,c\n1,,1\n18446744073709551615,\"x,\"\"y\"\"\",-1\n"
+        );
+    }
+
+    #[test]
+    fn test_tables_match_columns() {
+        let mut ledger = Ledger::default();
+        let distributor = ledger.distributors.entry(Default::default()).or_default();
+        distributor
+            .claimants
+            .insert(Default::default(), Default::default());
+        ledger.claims.push(crate::ledger::Claim {
+            origin: Default::default(),
+            distributor: Default::default(),
+            claimant: Default::default(),
+            index: 0,
+            root: [0; 32],
+            claim_amount: 0,
+            transfer_amount: Some(0),
+            forfeited_amount: Some(0),
+            destination: Some(Default::default()),
+            staked: Some(false),
+            stake_duration_epochs: Some(0),
+        });
+
+        for table in tables(&ledger) {
+            assert_eq!(table.rows.len(), 1, "{}", table.name);
+            assert_eq!(table.rows[0].len(), table.columns.len(), "{}", table.name);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Example integration claiming from a merkle distributor on behalf of a PDA, e.g. the vault of a
+//! DAO or multisig whose address is a leaf of the tree.
+//!
+//! The PDA signs [merkle_distributor::merkle_distributor::claim] through `invoke_signed`, using
+//! the CPI interface the merkle distributor exposes under its `cpi` feature. The claimed tokens go
+//! to a token account owned by the PDA, so they can only leave through this program.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use merkle_distributor::claim::LeafTerms;
+use merkle_distributor::program::MerkleDistributor;
+
+declare_id!("3NirRZuhvNeoCyAyDvr1MmuFU9EvoJMqKvT4qvBcgi5d");
+
+/// Seed of the vault PDA of an owner.
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// The [example_pda_claimer] program.
+#[program]
+pub mod example_pda_claimer {
+    use super::*;
+
+    /// Claims the leaf of the owner's vault PDA into the vault's token account. The owner pays for
+    /// the claim status.
+    pub fn claim(
+        ctx: Context<Claim>,
+        index: u64,
+        amount: u64,
+        terms: Option<LeafTerms>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let seeds: &[&[u8]] = &[VAULT_SEED, owner.as_ref(), &[ctx.bumps.vault]];
+
+        merkle_distributor::cpi::claim(
+            CpiContext::new_with_signer(
+                ctx.accounts.merkle_distributor_program.to_account_info(),
+                merkle_distributor::cpi::accounts::Claim {
+                    distributor: ctx.accounts.distributor.to_account_info(),
+                    claim_status: ctx.accounts.claim_status.to_account_info(),
+                    from: ctx.accounts.from.to_account_info(),
+                    to: ctx.accounts.vault_tokens.to_account_info(),
+                    claimant: ctx.accounts.vault.to_account_info(),
+                    payer: ctx.accounts.owner.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    screening_flag: ctx
+                        .accounts
+                        .screening_flag
+                        .as_ref()
+                        .map(|a| a.to_account_info()),
+                    rebind: None,
+                    leaf_screening_flag: None,
+                },
+                &[seeds],
+            ),
+            index,
+            amount,
+            terms,
+            proof,
+        )
+    }
+}
+
+/// Accounts for [example_pda_claimer::claim].
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    /// Owner of the vault, paying for the claim.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: The vault PDA, the claimant of the leaf. Only signs.
+    #[account(seeds = [VAULT_SEED, owner.key().as_ref()], bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// Token account of the vault receiving the claim.
+    #[account(mut, token::authority = vault)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    /// CHECK: The distributor, checked by the merkle distributor.
+    #[account(mut)]
+    pub distributor: UncheckedAccount<'info>,
+
+    /// CHECK: The vault's claim status, checked by the merkle distributor.
+    #[account(mut)]
+    pub claim_status: UncheckedAccount<'info>,
+
+    /// CHECK: The distributor's vault, checked by the merkle distributor.
+    #[account(mut)]
+    pub from: UncheckedAccount<'info>,
+
+    /// The merkle distributor program.
+    pub merkle_distributor_program: Program<'info, MerkleDistributor>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: The vault's screening flag account, if the distributor has a screening program.
+    pub screening_flag: Option<UncheckedAccount<'info>>,
+}
@@ -0,0 +1,1340 @@
+//! Validation and accounting shared by every claim instruction.
+//!
+//! A claim instruction validates the claim into a [ClaimTicket], moves `transfer_amount` tokens
+//! wherever it sends them, then records the ticket against the [MerkleDistributor] and
+//! [ClaimStatus]. Only the token movement differs between instructions.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token;
+
+use crate::merkle_proof;
+use crate::{
+    ClaimRebind, ClaimStatus, ClaimedEvent, ErrorCode, MerkleDistributor, RateLimit,
+    RateLimitTrippedEvent,
+};
+
+/// Where the claimed tokens end up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClaimKind {
+    /// Sent to the claimant, see [crate::merkle_distributor::claim], or to the destination it
+    /// approved, see [crate::merkle_distributor::claim_as_delegate].
+    Direct,
+    /// Locked up, see [crate::merkle_distributor::claim_stake] and
+    /// [crate::merkle_distributor::claim_into_program].
+    Stake,
+}
+
+/// A leaf of the distributor's Merkle tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClaimLeaf {
+    /// Index of the leaf.
+    pub index: u64,
+    /// Who the allocation belongs to.
+    pub claimant: Pubkey,
+    /// Total amount allocated to the claimant.
+    pub amount: u64,
+    /// Per-claimant terms, only part of [LeafVersion::V2] leaves.
+    pub terms: Option<LeafTerms>,
+}
+
+impl ClaimLeaf {
+    /// Hash of the leaf in `distributor`'s tree, laid out per its [LeafVersion] and hashed per its
+    /// [merkle_proof::HashScheme] and [merkle_proof::HashFunction].
+    pub fn hash(&self, distributor: &MerkleDistributor) -> Result<[u8; 32]> {
+        let scheme = distributor.hash_scheme;
+        let hasher = &distributor.hash_function;
+        let index = self.index.to_le_bytes();
+        let amount = self.amount.to_le_bytes();
+        match distributor.leaf_version {
+            LeafVersion::V0 => scheme.hash_leaf(hasher, &[&index, self.claimant.as_ref(), &amount]),
+            LeafVersion::V1 => scheme.hash_leaf(
+                hasher,
+                &[
+                    &[LeafVersion::V1 as u8],
+                    &index,
+                    self.claimant.as_ref(),
+                    &amount,
+                    distributor.base.as_ref(),
+                    distributor.mint.as_ref(),
+                ],
+            ),
+            LeafVersion::V2 => {
+                let terms = self.terms.unwrap_or_default();
+                scheme.hash_leaf(
+                    hasher,
+                    &[
+                        &[LeafVersion::V2 as u8],
+                        &index,
+                        self.claimant.as_ref(),
+                        &amount,
+                        distributor.base.as_ref(),
+                        distributor.mint.as_ref(),
+                        &terms.unlock_ts.to_le_bytes(),
+                        &terms.vesting_duration_seconds.to_le_bytes(),
+                        &terms.stake_duration_epochs.to_le_bytes(),
+                        &[terms.category],
+                    ],
+                )
+            }
+        }
+    }
+
+    /// Whether `proof` proves the leaf is part of `distributor`'s tree.
+    pub fn verify(&self, distributor: &MerkleDistributor, proof: Vec<[u8; 32]>) -> Result<bool> {
+        merkle_proof::verify(
+            proof,
+            distributor.root,
+            self.hash(distributor)?,
+            distributor.hash_scheme,
+            &distributor.hash_function,
+        )
+    }
+
+    /// Amount of the leaf unlocked at `now`: all of it unless its terms vest it.
+    pub fn vested_amount(&self, now: u64) -> u64 {
+        let Some(terms) = self.terms else {
+            return self.amount;
+        };
+        if now < terms.unlock_ts {
+            0
+        } else if now - terms.unlock_ts >= terms.vesting_duration_seconds {
+            self.amount
+        } else {
+            (self.amount as u128 * (now - terms.unlock_ts) as u128
+                / terms.vesting_duration_seconds as u128) as u64
+        }
+    }
+
+    /// Minimum number of epochs the leaf must be staked for; zero if it can be claimed directly.
+    pub fn stake_duration_epochs(&self) -> u32 {
+        self.terms.map_or(0, |terms| terms.stake_duration_epochs)
+    }
+}
+
+/// Terms of a single claimant, committed to by [LeafVersion::V2] leaves.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LeafTerms {
+    /// Timestamp vesting starts at; nothing can be claimed before it.
+    pub unlock_ts: u64,
+    /// The leaf vests linearly over this many seconds after `unlock_ts`; zero unlocks it all at
+    /// once.
+    pub vesting_duration_seconds: u64,
+    /// Minimum stake duration of [crate::merkle_distributor::claim_stake]; if non-zero the leaf
+    /// can only be claimed to stake.
+    pub stake_duration_epochs: u32,
+    /// Free-form tag of the allocation, e.g. investors, team or community.
+    pub category: u8,
+}
+
+/// Layout of the data hashed into a leaf.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum LeafVersion {
+    /// `index || claimant || amount`.
+    #[default]
+    V0,
+    /// `1u8 || index || claimant || amount || base || mint`.
+    ///
+    /// The distributor address is derived from `base`, so a tree built for one distributor
+    /// cannot be replayed against another one, or against the same base with another mint.
+    V1,
+    /// `2u8 || index || claimant || amount || base || mint || unlock_ts ||
+    /// vesting_duration_seconds || stake_duration_epochs || category`, see [LeafTerms].
+    ///
+    /// Leaves are bound like [LeafVersion::V1], and may be claimed in parts as they vest.
+    V2,
+}
+
+/// How a leaf is proven to be part of the distributor's tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LeafProof {
+    /// Sibling hashes from the leaf up to the root, see [merkle_proof::verify].
+    Path(Vec<[u8; 32]>),
+    /// Already proven together with other leaves by [merkle_proof::verify_multi].
+    Multi,
+}
+
+/// A claim that passed validation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClaimTicket {
+    /// The proven leaf.
+    pub leaf: ClaimLeaf,
+    /// Amount of the leaf vested but not yet claimed; counted against the distributor caps.
+    pub claim_amount: u64,
+    /// Amount leaving the vault after the early claim ramp.
+    pub transfer_amount: u64,
+}
+
+impl ClaimTicket {
+    /// Validates a claim of `leaf` at `now`: the leaf terms, the claim window, the claim kind,
+    /// revocation, rebinding, the claimable amount, the authority and the Merkle proof.
+    ///
+    /// `authority` is who authorized the claim: the leaf's claimant, by signing the instruction or
+    /// through its [crate::ClaimDelegate], or the admin pushing it with
+    /// [crate::merkle_distributor::distribute_batch]. Whoever pays for the claim is not checked.
+    pub fn validate(
+        distributor: &MerkleDistributor,
+        claim_status: &ClaimStatus,
+        leaf: ClaimLeaf,
+        proof: LeafProof,
+        authority: Pubkey,
+        now: u64,
+        kind: ClaimKind,
+    ) -> Result<Self> {
+        require!(
+            leaf.terms.is_some() == (distributor.leaf_version == LeafVersion::V2),
+            ErrorCode::InvalidLeafTerms
+        );
+        if kind == ClaimKind::Direct {
+            require!(
+                !distributor.stake_claim_only && leaf.stake_duration_epochs() == 0,
+                ErrorCode::MustClaimDirectToStake
+            );
+        }
+        require!(
+            now >= distributor.claim_start_ts && now <= distributor.get_claim_deadline(),
+            ErrorCode::OutsideClaimWindow
+        );
+        require!(claim_status.revoked_at == 0, ErrorCode::ClaimRevoked);
+        // Rebound leaves are claimed by the new wallet only, through a claim status that holds
+        // nothing but the rebound leaf.
+        require!(
+            claim_status.rebound_to == Pubkey::default()
+                && (claim_status.claimant == Pubkey::default()
+                    || claim_status.claimant == leaf.claimant),
+            ErrorCode::ClaimRebound
+        );
+        let vested_amount = leaf.vested_amount(now);
+        require!(
+            claim_status.claimed_amount < vested_amount,
+            ErrorCode::NoClaimableAmount
+        );
+
+        require!(
+            authority == leaf.claimant || authority == distributor.admin_auth,
+            ErrorCode::Unauthorized
+        );
+
+        if let LeafProof::Path(proof) = proof {
+            require!(leaf.verify(distributor, proof)?, ErrorCode::InvalidProof);
+        }
+
+        let claim_amount = vested_amount
+            .checked_sub(claim_status.claimed_amount)
+            .unwrap();
+        let transfer_amount =
+            distributor.get_transfer_amount(claim_amount, now, kind == ClaimKind::Stake);
+
+        Ok(Self {
+            leaf,
+            claim_amount,
+            transfer_amount,
+        })
+    }
+
+    /// Marks the claimed part of the leaf claimed and counts it against the distributor caps.
+    /// `payer` is recorded as the rent payer of a newly created `claim_status`.
+    pub fn record(
+        &self,
+        distributor: &mut MerkleDistributor,
+        claim_status: &mut ClaimStatus,
+        payer: Pubkey,
+        claimed_at: i64,
+    ) -> Result<()> {
+        if claim_status.num_claims == 0 {
+            claim_status.payer = payer;
+        }
+        claim_status.claimed_amount = claim_status
+            .claimed_amount
+            .checked_add(self.claim_amount)
+            .unwrap();
+        claim_status.num_claims = claim_status.num_claims.checked_add(1).unwrap();
+        claim_status.claimed_at = claimed_at;
+        claim_status.claimant = self.leaf.claimant;
+        claim_status.index = self.leaf.index;
+        claim_status.root = distributor.root;
+
+        distributor.total_amount_claimed = distributor
+            .total_amount_claimed
+            .checked_add(self.claim_amount)
+            .unwrap();
+        require!(
+            distributor.total_amount_claimed <= distributor.max_total_claim,
+            ErrorCode::ExceededMaxClaim
+        );
+
+        // A vesting leaf counts as a node once it is claimed in full.
+        if claim_status.claimed_amount == self.leaf.amount {
+            distributor.num_nodes_claimed = distributor.num_nodes_claimed.checked_add(1).unwrap();
+            require!(
+                distributor.num_nodes_claimed <= distributor.max_num_nodes,
+                ErrorCode::ExceededMaxNumNodes
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Caps the claim to what the distributor's rate limits still allow at `now`, then counts it
+    /// against them. Leaves larger than a limit are thereby claimed in parts over several windows.
+    /// Fails with [ErrorCode::RateLimited] if the current windows allow nothing more.
+    pub fn cap_to_rate_limit(
+        &mut self,
+        distributor: &mut MerkleDistributor,
+        claim_status: &mut ClaimStatus,
+        now: u64,
+    ) -> Result<()> {
+        let window_seconds = distributor.rate_limit_window_seconds;
+        if window_seconds == 0 {
+            return Ok(());
+        }
+
+        let allowance = [
+            distributor.rate_limit_window.remaining(
+                now,
+                window_seconds,
+                distributor.max_claim_per_window,
+            ),
+            claim_status.rate_limit_window.remaining(
+                now,
+                window_seconds,
+                distributor.max_claimant_claim_per_window,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        if let Some(allowance) = allowance.filter(|&allowance| self.transfer_amount > allowance) {
+            // Keep the early claim ramp's ratio between the claimed and transferred amounts.
+            let claim_amount = (self.claim_amount as u128 * allowance as u128
+                / self.transfer_amount as u128) as u64;
+            require!(claim_amount > 0, ErrorCode::RateLimited);
+            self.transfer_amount = (self.transfer_amount as u128 * claim_amount as u128
+                / self.claim_amount as u128) as u64;
+            self.claim_amount = claim_amount;
+        }
+
+        require!(
+            self.rate_limit(distributor, claim_status, now).is_none(),
+            ErrorCode::RateLimited
+        );
+        Ok(())
+    }
+
+    /// Counts the claim against the distributor's rate limits at `now`. If it would exceed one,
+    /// nothing is counted and the tripped limit is returned; the claim must then be skipped and
+    /// [Self::emit_rate_limited] called instead.
+    pub fn rate_limit(
+        &self,
+        distributor: &mut MerkleDistributor,
+        claim_status: &mut ClaimStatus,
+        now: u64,
+    ) -> Option<RateLimit> {
+        let window_seconds = distributor.rate_limit_window_seconds;
+        if window_seconds == 0 {
+            return None;
+        }
+
+        let mut global = distributor.rate_limit_window;
+        if !global.try_add(
+            self.transfer_amount,
+            now,
+            window_seconds,
+            distributor.max_claim_per_window,
+        ) {
+            return Some(RateLimit::Global);
+        }
+        let mut claimant = claim_status.rate_limit_window;
+        if !claimant.try_add(
+            self.transfer_amount,
+            now,
+            window_seconds,
+            distributor.max_claimant_claim_per_window,
+        ) {
+            return Some(RateLimit::Claimant);
+        }
+
+        distributor.rate_limit_window = global;
+        claim_status.rate_limit_window = claimant;
+        None
+    }
+
+    /// Emits the [RateLimitTrippedEvent] for this claim, skipped because it would exceed `limit`.
+    pub fn emit_rate_limited(&self, distributor: &Account<MerkleDistributor>, limit: RateLimit) {
+        emit!(RateLimitTrippedEvent {
+            distributor: distributor.key(),
+            claimant: self.leaf.claimant,
+            index: self.leaf.index,
+            transfer_amount: self.transfer_amount,
+            limit,
+        });
+    }
+
+    /// Emits the [ClaimedEvent] for this claim into `destination`, staked for
+    /// `stake_duration_epochs` if it was staked.
+    pub fn emit(
+        &self,
+        distributor: &Account<MerkleDistributor>,
+        destination: Pubkey,
+        stake_duration_epochs: Option<u32>,
+    ) {
+        emit!(ClaimedEvent {
+            root: distributor.root,
+            index: self.leaf.index,
+            claimant: self.leaf.claimant,
+            claim_amount: self.claim_amount,
+            distributor: distributor.key(),
+            transfer_amount: self.transfer_amount,
+            forfeited_amount: self.claim_amount - self.transfer_amount,
+            destination,
+            staked: stake_duration_epochs.is_some(),
+            stake_duration_epochs: stake_duration_epochs.unwrap_or_default(),
+        });
+    }
+}
+
+/// Returns the claimant of the leaf claimed by the signing `claimant`. A wallet claiming a leaf
+/// rebound to it with [crate::merkle_distributor::rebind_claim] claims with the original
+/// claimant's authority.
+pub fn rebound_leaf_claimant(claimant: Pubkey, rebind: Option<&ClaimRebind>) -> Pubkey {
+    rebind.map_or(claimant, |rebind| rebind.claimant)
+}
+
+/// Seed prefix of the claim status of the leaf claimed by the signing claimant. A rebound leaf
+/// keeps its own status at `["ReboundClaimStatus", distributor, original claimant]`, apart from the
+/// `["ClaimStatus", distributor, claimant]` of any leaf of the new wallet's own.
+pub fn claim_status_seed(rebind: Option<&ClaimRebind>) -> &'static [u8] {
+    match rebind {
+        Some(_) => b"ReboundClaimStatus",
+        None => b"ClaimStatus",
+    }
+}
+
+/// Seed of the account a screening program flags a claimant with, see
+/// [MerkleDistributor::screening_program].
+pub const SCREENING_FLAG_SEED: &[u8] = b"flagged";
+
+/// Checks `claimant` against the distributor's screening program, if it has one.
+///
+/// `screening_flag` must be the account at `[SCREENING_FLAG_SEED, claimant]` of the screening
+/// program. The claimant is flagged if the screening program initialized it.
+pub fn check_screening(
+    distributor: &MerkleDistributor,
+    claimant: &Pubkey,
+    screening_flag: Option<&AccountInfo>,
+) -> Result<()> {
+    if distributor.screening_program == Pubkey::default() {
+        return Ok(());
+    }
+    let screening_flag = screening_flag.ok_or(ErrorCode::InvalidScreeningFlag)?;
+    let (key, _) = Pubkey::find_program_address(
+        &[SCREENING_FLAG_SEED, claimant.as_ref()],
+        &distributor.screening_program,
+    );
+    require_keys_eq!(screening_flag.key(), key, ErrorCode::InvalidScreeningFlag);
+    require!(
+        screening_flag.owner != &distributor.screening_program || screening_flag.data_is_empty(),
+        ErrorCode::ClaimantFlagged
+    );
+    Ok(())
+}
+
+/// Checks that `vault` is the distributor's ATA, for vaults passed as remaining accounts.
+pub fn check_vault(
+    distributor_key: &Pubkey,
+    distributor: &MerkleDistributor,
+    vault: &Pubkey,
+) -> Result<()> {
+    let ata = get_associated_token_address(distributor_key, &distributor.mint);
+    require!(ata == *vault, ErrorCode::InvalidDistributorTokenAccount);
+    Ok(())
+}
+
+/// Checks that `to` is a token account of `owner` for the distributor's mint, for token accounts
+/// passed as remaining accounts. Named accounts are checked by their constraints instead.
+pub fn check_destination(
+    distributor: &MerkleDistributor,
+    to: &token::TokenAccount,
+    owner: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(to.owner, *owner, ErrorCode::OwnerMismatch);
+    require_keys_eq!(to.mint, distributor.mint, ErrorCode::MintMismatch);
+    Ok(())
+}
+
+/// Loads the [ClaimStatus] of `claimant` from an unchecked account, creating it first if needed.
+/// Used by instructions that take their claims through remaining accounts.
+pub fn load_or_init_claim_status<'info>(
+    info: &'info AccountInfo<'info>,
+    distributor: &Pubkey,
+    claimant: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<Account<'info, ClaimStatus>> {
+    let (key, bump) = Pubkey::find_program_address(
+        &[
+            b"ClaimStatus".as_ref(),
+            distributor.as_ref(),
+            claimant.as_ref(),
+        ],
+        &crate::ID,
+    );
+    require_keys_eq!(key, info.key(), ErrorCode::InvalidClaimStatus);
+
+    if info.owner == &system_program::ID {
+        let seeds = [
+            b"ClaimStatus".as_ref(),
+            distributor.as_ref(),
+            claimant.as_ref(),
+            &[bump],
+        ];
+        let space = 8 + ClaimStatus::LEN;
+        let rent = Rent::get()?.minimum_balance(space);
+
+        // Same as anchor's `init`: the account may already hold lamports.
+        if info.lamports() == 0 {
+            system_program::create_account(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::CreateAccount {
+                        from: payer.clone(),
+                        to: info.clone(),
+                    },
+                )
+                .with_signer(&[&seeds[..]]),
+                rent,
+                space as u64,
+                &crate::ID,
+            )?;
+        } else {
+            let top_up = rent.saturating_sub(info.lamports());
+            if top_up > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        system_program.clone(),
+                        system_program::Transfer {
+                            from: payer.clone(),
+                            to: info.clone(),
+                        },
+                    ),
+                    top_up,
+                )?;
+            }
+            system_program::allocate(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Allocate {
+                        account_to_allocate: info.clone(),
+                    },
+                )
+                .with_signer(&[&seeds[..]]),
+                space as u64,
+            )?;
+            system_program::assign(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Assign {
+                        account_to_assign: info.clone(),
+                    },
+                )
+                .with_signer(&[&seeds[..]]),
+                &crate::ID,
+            )?;
+        }
+
+        info.try_borrow_mut_data()?[..8].copy_from_slice(&ClaimStatus::DISCRIMINATOR);
+    }
+
+    Account::try_from(info)
+}
+
+/// Transfers `amount` out of the distributor vault, signed by the distributor PDA.
+pub fn transfer_from_vault<'info>(
+    distributor: &Account<'info, MerkleDistributor>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    require!(!distributor.native, ErrorCode::NativeDistributor);
+    let seeds = [
+        b"MerkleDistributor".as_ref(),
+        &distributor.base.to_bytes(),
+        &[distributor.bump],
+    ];
+
+    token::transfer(
+        CpiContext::new(
+            token_program,
+            token::Transfer {
+                from,
+                to,
+                authority: distributor.to_account_info(),
+            },
+        )
+        .with_signer(&[&seeds[..]]),
+        amount,
+    )
+}
+
+/// Lamports a native distributor holds above its rent exempt minimum.
+pub fn native_vault_amount(distributor: &AccountInfo) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(distributor.data_len());
+    Ok(distributor.lamports().saturating_sub(rent_exempt))
+}
+
+/// Transfers `amount` lamports out of a native distributor, keeping it rent exempt. The program
+/// owns the distributor account, so it debits it directly.
+pub fn transfer_native<'info>(
+    distributor: &Account<'info, MerkleDistributor>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let from = distributor.to_account_info();
+    require!(
+        amount <= native_vault_amount(&from)?,
+        ErrorCode::InsufficientNativeBalance
+    );
+
+    **from.try_borrow_mut_lamports()? -= amount;
+    **to.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RateLimitWindow, PERCENT_100};
+
+    fn leaves() -> [ClaimLeaf; 2] {
+        [
+            ClaimLeaf {
+                index: 0,
+                claimant: Pubkey::new_unique(),
+                amount: 1_000,
+                terms: None,
+            },
+            ClaimLeaf {
+                index: 1,
+                claimant: Pubkey::new_unique(),
+                amount: 2_000,
+                terms: None,
+            },
+        ]
+    }
+
+    fn root(distributor: &MerkleDistributor, leaves: &[ClaimLeaf; 2]) -> [u8; 32] {
+        distributor
+            .hash_scheme
+            .hash_pair(
+                &distributor.hash_function,
+                leaves[0].hash(distributor).unwrap(),
+                leaves[1].hash(distributor).unwrap(),
+            )
+            .unwrap()
+    }
+
+    fn distributor(leaves: &[ClaimLeaf; 2]) -> MerkleDistributor {
+        let mut distributor = MerkleDistributor {
+            admin_auth: Pubkey::new_unique(),
+            max_total_claim: 3_000,
+            max_num_nodes: 2,
+            claim_start_ts: 100,
+            claim_end_ts: 200,
+            immediate_claim_percentage: PERCENT_100,
+            ..MerkleDistributor::default()
+        };
+        distributor.root = root(&distributor, leaves);
+        distributor
+    }
+
+    fn validate(
+        distributor: &MerkleDistributor,
+        claim_status: &ClaimStatus,
+        leaves: &[ClaimLeaf; 2],
+        authority: Pubkey,
+        now: u64,
+        kind: ClaimKind,
+    ) -> Result<ClaimTicket> {
+        ClaimTicket::validate(
+            distributor,
+            claim_status,
+            leaves[0],
+            LeafProof::Path(vec![leaves[1].hash(distributor).unwrap()]),
+            authority,
+            now,
+            kind,
+        )
+    }
+
+    #[test]
+    fn validates_and_records_claim() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        let mut claim_status = ClaimStatus::default();
+
+        let ticket = validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            leaves[0].claimant,
+            150,
+            ClaimKind::Direct,
+        )
+        .unwrap();
+        assert_eq!(ticket.claim_amount, 1_000);
+        assert_eq!(ticket.transfer_amount, 1_000);
+
+        ticket
+            .record(&mut distributor, &mut claim_status, leaves[0].claimant, 150)
+            .unwrap();
+        assert_eq!(claim_status.claimed_amount, 1_000);
+        assert_eq!(claim_status.claimant, leaves[0].claimant);
+        assert_eq!(claim_status.index, leaves[0].index);
+        assert_eq!(claim_status.root, distributor.root);
+        assert_eq!(claim_status.num_claims, 1);
+        assert_eq!(claim_status.payer, leaves[0].claimant);
+        assert_eq!(distributor.total_amount_claimed, 1_000);
+        assert_eq!(distributor.num_nodes_claimed, 1);
+
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                leaves[0].claimant,
+                150,
+                ClaimKind::Direct,
+            )
+            .unwrap_err(),
+            error!(ErrorCode::NoClaimableAmount)
+        );
+    }
+
+    #[test]
+    fn admin_or_claimant_may_authorize() {
+        let leaves = leaves();
+        let distributor = distributor(&leaves);
+        let claim_status = ClaimStatus::default();
+
+        assert!(validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            leaves[0].claimant,
+            150,
+            ClaimKind::Direct,
+        )
+        .is_ok());
+        assert!(validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            distributor.admin_auth,
+            150,
+            ClaimKind::Direct,
+        )
+        .is_ok());
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                Pubkey::new_unique(),
+                150,
+                ClaimKind::Direct,
+            )
+            .unwrap_err(),
+            error!(ErrorCode::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_proof() {
+        let leaves = leaves();
+        let distributor = distributor(&leaves);
+
+        let forged = ClaimLeaf {
+            amount: 5_000,
+            ..leaves[0]
+        };
+        assert_eq!(
+            ClaimTicket::validate(
+                &distributor,
+                &ClaimStatus::default(),
+                forged,
+                LeafProof::Path(vec![leaves[1].hash(&distributor).unwrap()]),
+                forged.claimant,
+                150,
+                ClaimKind::Direct,
+            )
+            .unwrap_err(),
+            error!(ErrorCode::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn bound_leaves_only_verify_against_their_distributor() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        distributor.leaf_version = LeafVersion::V1;
+        distributor.base = Pubkey::new_unique();
+        distributor.mint = Pubkey::new_unique();
+        distributor.root = root(&distributor, &leaves);
+        let claim_status = ClaimStatus::default();
+        let payer = leaves[0].claimant;
+        assert!(validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            payer,
+            150,
+            ClaimKind::Direct
+        )
+        .is_ok());
+
+        // Same root and proof, another distributor or mint.
+        for other in [
+            MerkleDistributor {
+                base: Pubkey::new_unique(),
+                ..distributor.clone()
+            },
+            MerkleDistributor {
+                mint: Pubkey::new_unique(),
+                ..distributor.clone()
+            },
+        ] {
+            assert_eq!(
+                ClaimTicket::validate(
+                    &other,
+                    &claim_status,
+                    leaves[0],
+                    LeafProof::Path(vec![leaves[1].hash(&distributor).unwrap()]),
+                    payer,
+                    150,
+                    ClaimKind::Direct,
+                )
+                .unwrap_err(),
+                error!(ErrorCode::InvalidProof)
+            );
+        }
+    }
+
+    #[test]
+    fn vesting_leaves_are_claimed_as_they_vest() {
+        let mut leaves = leaves();
+        for leaf in &mut leaves {
+            leaf.terms = Some(LeafTerms {
+                unlock_ts: 120,
+                vesting_duration_seconds: 40,
+                ..LeafTerms::default()
+            });
+        }
+        let mut distributor = distributor(&leaves);
+        distributor.leaf_version = LeafVersion::V2;
+        distributor.root = root(&distributor, &leaves);
+        let mut claim_status = ClaimStatus::default();
+        let payer = leaves[0].claimant;
+
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                payer,
+                110,
+                ClaimKind::Direct
+            )
+            .unwrap_err(),
+            error!(ErrorCode::NoClaimableAmount)
+        );
+
+        // A quarter vested, then the rest, pushed by the admin.
+        for (num_claims, (now, claimed, nodes, payer)) in [
+            (130, 250, 0, payer),
+            (170, 1_000, 1, distributor.admin_auth),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let ticket = validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                payer,
+                now,
+                ClaimKind::Direct,
+            )
+            .unwrap();
+            ticket
+                .record(&mut distributor, &mut claim_status, payer, now as i64)
+                .unwrap();
+            assert_eq!(claim_status.claimed_amount, claimed);
+            assert_eq!(claim_status.num_claims, num_claims as u64 + 1);
+            assert_eq!(claim_status.payer, leaves[0].claimant);
+            assert_eq!(distributor.total_amount_claimed, claimed);
+            assert_eq!(distributor.num_nodes_claimed, nodes);
+        }
+
+        // Leaves without terms do not verify against a V2 distributor.
+        let mut bare = leaves;
+        bare[0].terms = None;
+        assert_eq!(
+            validate(
+                &distributor,
+                &ClaimStatus::default(),
+                &bare,
+                payer,
+                170,
+                ClaimKind::Direct
+            )
+            .unwrap_err(),
+            error!(ErrorCode::InvalidLeafTerms)
+        );
+    }
+
+    #[test]
+    fn leaves_requiring_stake_cannot_be_claimed_directly() {
+        let mut leaves = leaves();
+        leaves[0].terms = Some(LeafTerms {
+            stake_duration_epochs: 12,
+            ..LeafTerms::default()
+        });
+        leaves[1].terms = Some(LeafTerms::default());
+        let mut distributor = distributor(&leaves);
+        distributor.leaf_version = LeafVersion::V2;
+        distributor.root = root(&distributor, &leaves);
+        let claim_status = ClaimStatus::default();
+        let payer = leaves[0].claimant;
+
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                payer,
+                150,
+                ClaimKind::Direct
+            )
+            .unwrap_err(),
+            error!(ErrorCode::MustClaimDirectToStake)
+        );
+        let ticket = validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            payer,
+            150,
+            ClaimKind::Stake,
+        )
+        .unwrap();
+        assert_eq!(ticket.leaf.stake_duration_epochs(), 12);
+    }
+
+    #[test]
+    fn checks_screening_flag() {
+        let claimant = Pubkey::new_unique();
+        let mut distributor = MerkleDistributor::default();
+        assert!(check_screening(&distributor, &claimant, None).is_ok());
+
+        distributor.screening_program = Pubkey::new_unique();
+        let (key, _) = Pubkey::find_program_address(
+            &[SCREENING_FLAG_SEED, claimant.as_ref()],
+            &distributor.screening_program,
+        );
+        let other = Pubkey::new_unique();
+        let (mut lamports, mut data) = (0, vec![]);
+        let unflagged = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &system_program::ID,
+            false,
+            0,
+        );
+        let (mut other_lamports, mut other_data) = (0, vec![]);
+        let mismatched = AccountInfo::new(
+            &other,
+            false,
+            false,
+            &mut other_lamports,
+            &mut other_data,
+            &system_program::ID,
+            false,
+            0,
+        );
+        let (mut flag_lamports, mut flag_data) = (1, vec![1]);
+        let flagged = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut flag_lamports,
+            &mut flag_data,
+            &distributor.screening_program,
+            false,
+            0,
+        );
+
+        assert!(check_screening(&distributor, &claimant, Some(&unflagged)).is_ok());
+        for screening_flag in [None, Some(&mismatched)] {
+            assert_eq!(
+                check_screening(&distributor, &claimant, screening_flag).unwrap_err(),
+                error!(ErrorCode::InvalidScreeningFlag)
+            );
+        }
+        assert_eq!(
+            check_screening(&distributor, &claimant, Some(&flagged)).unwrap_err(),
+            error!(ErrorCode::ClaimantFlagged)
+        );
+    }
+
+    #[test]
+    fn rejects_revoked_claims() {
+        let leaves = leaves();
+        let distributor = distributor(&leaves);
+        let claim_status = ClaimStatus {
+            revoked_at: 120,
+            ..ClaimStatus::default()
+        };
+
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                leaves[0].claimant,
+                150,
+                ClaimKind::Direct
+            )
+            .unwrap_err(),
+            error!(ErrorCode::ClaimRevoked)
+        );
+        assert!(leaves[0]
+            .verify(&distributor, vec![leaves[1].hash(&distributor).unwrap()])
+            .unwrap());
+        assert!(!leaves[0].verify(&distributor, vec![]).unwrap());
+    }
+
+    #[test]
+    fn rebound_claims() {
+        let leaves = leaves();
+        let distributor = distributor(&leaves);
+        let new_claimant = Pubkey::new_unique();
+        let rebind = ClaimRebind {
+            claimant: leaves[0].claimant,
+            new_claimant,
+            ..ClaimRebind::default()
+        };
+
+        // The original claimant is locked out.
+        let claim_status = ClaimStatus {
+            claimant: leaves[0].claimant,
+            rebound_to: new_claimant,
+            ..ClaimStatus::default()
+        };
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                leaves[0].claimant,
+                150,
+                ClaimKind::Direct
+            )
+            .unwrap_err(),
+            error!(ErrorCode::ClaimRebound)
+        );
+
+        // The new wallet claims with the original claimant's authority.
+        let claimant = rebound_leaf_claimant(new_claimant, Some(&rebind));
+        assert_eq!(claimant, leaves[0].claimant);
+        let rebound_claim_status = ClaimStatus {
+            claimant: leaves[0].claimant,
+            ..ClaimStatus::default()
+        };
+        let ticket = validate(
+            &distributor,
+            &rebound_claim_status,
+            &leaves,
+            claimant,
+            150,
+            ClaimKind::Direct,
+        )
+        .unwrap();
+        assert_eq!(ticket.leaf.claimant, leaves[0].claimant);
+
+        // Its claim status cannot be used for another leaf.
+        assert_eq!(
+            ClaimTicket::validate(
+                &distributor,
+                &rebound_claim_status,
+                leaves[1],
+                LeafProof::Path(vec![leaves[0].hash(&distributor).unwrap()]),
+                leaves[1].claimant,
+                150,
+                ClaimKind::Direct,
+            )
+            .unwrap_err(),
+            error!(ErrorCode::ClaimRebound)
+        );
+        assert_eq!(rebound_leaf_claimant(new_claimant, None), new_claimant);
+
+        // The rebound leaf is claimed through its own claim status.
+        assert_eq!(claim_status_seed(Some(&rebind)), b"ReboundClaimStatus");
+        assert_eq!(claim_status_seed(None), b"ClaimStatus");
+    }
+
+    #[test]
+    fn rejects_outside_window_and_direct_claims_on_stake_only() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        let claim_status = ClaimStatus::default();
+        let payer = leaves[0].claimant;
+
+        for now in [99, 201] {
+            assert_eq!(
+                validate(
+                    &distributor,
+                    &claim_status,
+                    &leaves,
+                    payer,
+                    now,
+                    ClaimKind::Direct
+                )
+                .unwrap_err(),
+                error!(ErrorCode::OutsideClaimWindow)
+            );
+        }
+
+        distributor.stake_claim_only = true;
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                payer,
+                150,
+                ClaimKind::Direct
+            )
+            .unwrap_err(),
+            error!(ErrorCode::MustClaimDirectToStake)
+        );
+        assert!(validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            payer,
+            150,
+            ClaimKind::Stake
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn applies_early_claim_ramp() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        distributor.immediate_claim_percentage = 50_000000;
+        distributor.later_claim_offset_seconds = 50;
+        let claim_status = ClaimStatus::default();
+        let payer = leaves[0].claimant;
+
+        // Halfway through the ramp: 50% + 50% * 25 / 50.
+        let ticket = validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            payer,
+            125,
+            ClaimKind::Direct,
+        )
+        .unwrap();
+        assert_eq!(ticket.claim_amount, 1_000);
+        assert_eq!(ticket.transfer_amount, 750);
+
+        // Staked claims are not ramped by default.
+        let ticket = validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            payer,
+            125,
+            ClaimKind::Stake,
+        )
+        .unwrap();
+        assert_eq!(ticket.transfer_amount, 1_000);
+    }
+
+    #[test]
+    fn accepts_reduced_claims_during_grace_period() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        distributor.grace_period_seconds = 20;
+        distributor.grace_claim_percentage = 30_000000;
+        let claim_status = ClaimStatus::default();
+        let payer = leaves[0].claimant;
+
+        let ticket = validate(
+            &distributor,
+            &claim_status,
+            &leaves,
+            payer,
+            220,
+            ClaimKind::Direct,
+        )
+        .unwrap();
+        assert_eq!(ticket.claim_amount, 1_000);
+        assert_eq!(ticket.transfer_amount, 300);
+
+        assert_eq!(
+            validate(
+                &distributor,
+                &claim_status,
+                &leaves,
+                payer,
+                221,
+                ClaimKind::Direct
+            )
+            .unwrap_err(),
+            error!(ErrorCode::OutsideClaimWindow)
+        );
+    }
+
+    #[test]
+    fn record_enforces_caps() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        distributor.max_total_claim = 999;
+
+        let ticket = validate(
+            &distributor,
+            &ClaimStatus::default(),
+            &leaves,
+            leaves[0].claimant,
+            150,
+            ClaimKind::Direct,
+        )
+        .unwrap();
+        assert_eq!(
+            ticket
+                .record(
+                    &mut distributor,
+                    &mut ClaimStatus::default(),
+                    leaves[0].claimant,
+                    150
+                )
+                .unwrap_err(),
+            error!(ErrorCode::ExceededMaxClaim)
+        );
+    }
+
+    #[test]
+    fn enforces_rate_limits() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        let tickets = [0, 1].map(|i| ClaimTicket {
+            leaf: leaves[i],
+            claim_amount: leaves[i].amount,
+            transfer_amount: leaves[i].amount,
+        });
+        let mut claim_statuses = [ClaimStatus::default(), ClaimStatus::default()];
+
+        // Disabled by default.
+        assert_eq!(
+            tickets[1].rate_limit(&mut distributor, &mut claim_statuses[1], 150),
+            None
+        );
+        assert_eq!(distributor.rate_limit_window, RateLimitWindow::default());
+
+        distributor.rate_limit_window_seconds = 60;
+        distributor.max_claim_per_window = 2_500;
+        distributor.max_claimant_claim_per_window = 1_500;
+
+        assert_eq!(
+            tickets[0].rate_limit(&mut distributor, &mut claim_statuses[0], 150),
+            None
+        );
+        let window = RateLimitWindow {
+            start_ts: 120,
+            claimed_amount: 1_000,
+        };
+        assert_eq!(distributor.rate_limit_window, window);
+        assert_eq!(claim_statuses[0].rate_limit_window, window);
+
+        // Nothing is counted for a tripped limit.
+        assert_eq!(
+            tickets[1].rate_limit(&mut distributor, &mut claim_statuses[1], 179),
+            Some(RateLimit::Global)
+        );
+        assert_eq!(
+            tickets[1].rate_limit(&mut distributor, &mut claim_statuses[1], 180),
+            Some(RateLimit::Claimant)
+        );
+        assert_eq!(distributor.rate_limit_window, window);
+        assert_eq!(
+            claim_statuses[1].rate_limit_window,
+            RateLimitWindow::default()
+        );
+
+        distributor.max_claimant_claim_per_window = 0;
+        assert_eq!(
+            tickets[1].rate_limit(&mut distributor, &mut claim_statuses[1], 180),
+            None
+        );
+        assert_eq!(
+            distributor.rate_limit_window,
+            RateLimitWindow {
+                start_ts: 180,
+                claimed_amount: 2_000,
+            }
+        );
+    }
+
+    #[test]
+    fn caps_claims_to_rate_limits() {
+        let leaves = leaves();
+        let mut distributor = distributor(&leaves);
+        distributor.rate_limit_window_seconds = 60;
+        distributor.max_claim_per_window = 2_500;
+        distributor.max_claimant_claim_per_window = 1_500;
+        let mut claim_status = ClaimStatus::default();
+        // Half of the claim is forfeited to the early claim ramp.
+        let mut ticket = ClaimTicket {
+            leaf: leaves[1],
+            claim_amount: 4_000,
+            transfer_amount: 2_000,
+        };
+
+        // Capped to the claimant's limit, keeping the ramp's ratio.
+        ticket
+            .cap_to_rate_limit(&mut distributor, &mut claim_status, 150)
+            .unwrap();
+        assert_eq!(ticket.claim_amount, 3_000);
+        assert_eq!(ticket.transfer_amount, 1_500);
+        assert_eq!(claim_status.rate_limit_window.claimed_amount, 1_500);
+
+        // The window has nothing left for the claimant.
+        let mut rest = ClaimTicket {
+            claim_amount: 1_000,
+            transfer_amount: 500,
+            ..ticket
+        };
+        assert_eq!(
+            rest.cap_to_rate_limit(&mut distributor, &mut claim_status, 179)
+                .unwrap_err(),
+            ErrorCode::RateLimited.into()
+        );
+
+        // The next window has room for the rest of the leaf.
+        rest.cap_to_rate_limit(&mut distributor, &mut claim_status, 180)
+            .unwrap();
+        assert_eq!(rest.claim_amount, 1_000);
+        assert_eq!(distributor.rate_limit_window.claimed_amount, 500);
+    }
+}
@@ -14,16 +14,28 @@
 //!
 //! The Merkle distributor program and SDK is distributed under the GPL v3.0 license.
 
+// Instructions such as `new_distributor` and `claim_stake` take their arguments individually, and
+// `#[program]` generates wrappers with the same signatures.
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::get_associated_token_address;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use zeta_staking::program::ZetaStaking;
 
+use crate::claim::{ClaimKind, ClaimLeaf, ClaimTicket, LeafProof, LeafTerms, LeafVersion};
+use crate::merkle_proof::{HashFunction, HashScheme, ProofFlag};
+
+pub mod claim;
 pub mod merkle_proof;
+pub mod merkle_tree;
 
 declare_id!("4JSALTb4QbipG7NNLryAFJg4y8X5C1ELErSncsVMA3gZ");
 
-const PERCENT_100: u64 = 100_000000;
+pub const PERCENT_100: u64 = 100_000000;
 
 /// The [merkle_distributor] program.
 #[program]
@@ -33,6 +45,8 @@ pub mod merkle_distributor {
 
     /// Creates a new [MerkleDistributor].
     /// After creating this [MerkleDistributor], the account should be seeded with tokens via its ATA.
+    /// A `native` distributor of the native mint is instead seeded by sending lamports to the
+    /// distributor account itself, see [merkle_distributor::claim_native].
     pub fn new_distributor(
         ctx: Context<NewDistributor>,
         root: [u8; 32],
@@ -43,7 +57,17 @@ pub mod merkle_distributor {
         stake_claim_only: bool,
         immediate_claim_percentage: u64,
         later_claim_offset_seconds: u64,
+        stake_claim_scaling: StakeClaimScaling,
+        stake_immediate_claim_percentage: u64,
+        hash_scheme: HashScheme,
+        leaf_version: LeafVersion,
+        hash_function: HashFunction,
+        native: bool,
     ) -> Result<()> {
+        require!(
+            !native || ctx.accounts.mint.key() == anchor_spl::token::spl_token::native_mint::ID,
+            ErrorCode::MintMismatch
+        );
         let distributor = &mut ctx.accounts.distributor;
 
         distributor.base = ctx.accounts.base.key();
@@ -52,6 +76,9 @@ pub mod merkle_distributor {
         distributor.bump = ctx.bumps.distributor;
 
         distributor.root = root;
+        distributor.hash_scheme = hash_scheme;
+        distributor.leaf_version = leaf_version;
+        distributor.hash_function = hash_function;
         distributor.mint = ctx.accounts.mint.key();
 
         distributor.max_total_claim = max_total_claim;
@@ -63,12 +90,130 @@ pub mod merkle_distributor {
         distributor.claim_start_ts = claim_start_ts;
         distributor.claim_end_ts = claim_end_ts;
         distributor.stake_claim_only = stake_claim_only;
+        // Native distributors cannot stake.
+        assert!(!(native && stake_claim_only));
+        distributor.native = native;
 
         assert!(later_claim_offset_seconds + claim_start_ts < claim_end_ts);
         assert!(immediate_claim_percentage <= PERCENT_100);
         distributor.immediate_claim_percentage = immediate_claim_percentage;
         distributor.later_claim_offset_seconds = later_claim_offset_seconds;
 
+        assert!(stake_immediate_claim_percentage <= PERCENT_100);
+        distributor.stake_claim_scaling = stake_claim_scaling;
+        distributor.stake_immediate_claim_percentage = stake_immediate_claim_percentage;
+
+        distributor.version = MerkleDistributor::VERSION;
+
+        emit!(DistributorCreatedEvent {
+            distributor: distributor.key(),
+            base: distributor.base,
+            admin_auth: distributor.admin_auth,
+            mint: distributor.mint,
+            root,
+            max_total_claim,
+            max_num_nodes,
+            claim_start_ts,
+            claim_end_ts,
+            stake_claim_only,
+            immediate_claim_percentage,
+            later_claim_offset_seconds,
+            stake_claim_scaling,
+            stake_immediate_claim_percentage,
+            hash_scheme,
+            leaf_version,
+            hash_function,
+            native,
+        });
+
+        Ok(())
+    }
+
+    /// Reallocates a [MerkleDistributor] created under an older layout to [MerkleDistributor::LEN]
+    /// and bumps it to [MerkleDistributor::VERSION].
+    /// The added bytes are zeroed, which is the default of every field added since.
+    pub fn migrate_distributor(ctx: Context<MigrateDistributor>) -> Result<()> {
+        let info = ctx.accounts.distributor.to_account_info();
+
+        // Older layouts cannot be deserialized yet, so check the admin against the raw data.
+        {
+            let data = info.try_borrow_data()?;
+            let admin_auth =
+                MerkleDistributor::ADMIN_AUTH_OFFSET..MerkleDistributor::ADMIN_AUTH_OFFSET + 32;
+            require!(
+                data.len() >= admin_auth.end && data[..8] == MerkleDistributor::DISCRIMINATOR,
+                ErrorCode::InvalidDistributorAccount
+            );
+            require!(
+                data[admin_auth] == ctx.accounts.admin_auth.key().to_bytes(),
+                ErrorCode::DistributorAdminMismatch
+            );
+        }
+
+        let space = 8 + MerkleDistributor::LEN;
+        if info.data_len() < space {
+            grow_account(
+                &info,
+                space,
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+
+        let mut data = info.try_borrow_mut_data()?;
+        let mut distributor = MerkleDistributor::try_deserialize(&mut &data[..])?;
+        require!(
+            distributor.version < MerkleDistributor::VERSION,
+            ErrorCode::DistributorAlreadyMigrated
+        );
+        distributor.version = MerkleDistributor::VERSION;
+        distributor.try_serialize(&mut &mut data[..])?;
+
+        emit!(DistributorMigratedEvent {
+            distributor: info.key(),
+            version: distributor.version,
+        });
+
+        Ok(())
+    }
+
+    /// Reallocates a [ClaimStatus] created under an older, shorter layout to [ClaimStatus::LEN].
+    /// The added bytes are zeroed, which is the default of every field added since. Anyone may pay
+    /// for it.
+    pub fn migrate_claim_status(ctx: Context<MigrateClaimStatus>) -> Result<()> {
+        let info = ctx.accounts.claim_status.to_account_info();
+        {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == ClaimStatus::DISCRIMINATOR,
+                ErrorCode::InvalidClaimStatus
+            );
+        }
+        let space = 8 + ClaimStatus::LEN;
+        require!(
+            info.data_len() < space,
+            ErrorCode::ClaimStatusAlreadyMigrated
+        );
+        grow_account(
+            &info,
+            space,
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        let mut claim_status = ClaimStatus::try_deserialize(&mut &data[..])?;
+        // Older layouts only recorded the claimed amount, claimed in a single claim.
+        if claim_status.num_claims == 0 && claim_status.claimed_amount > 0 {
+            claim_status.num_claims = 1;
+        }
+        claim_status.try_serialize(&mut &mut data[..])?;
+
+        emit!(ClaimStatusMigratedEvent {
+            claim_status: info.key(),
+            claimant: claim_status.claimant,
+        });
+
         Ok(())
     }
 
@@ -86,12 +231,24 @@ pub mod merkle_distributor {
         distributor.max_num_nodes = max_num_nodes;
         distributor.num_nodes_claimed = 0;
 
+        emit!(RootUpdatedEvent {
+            distributor: distributor.key(),
+            root,
+            max_total_claim,
+            max_num_nodes,
+        });
+
         Ok(())
     }
 
     pub fn update_distributor_admin_auth(ctx: Context<UpdateDistributorAdminAuth>) -> Result<()> {
         let distributor = &mut ctx.accounts.distributor;
 
+        emit!(AdminAuthUpdatedEvent {
+            distributor: distributor.key(),
+            old_admin_auth: distributor.admin_auth,
+            new_admin_auth: ctx.accounts.new_admin.key(),
+        });
         distributor.admin_auth = ctx.accounts.new_admin.key();
 
         Ok(())
@@ -107,6 +264,12 @@ pub mod merkle_distributor {
         distributor.claim_start_ts = claim_start_ts;
         distributor.claim_end_ts = claim_end_ts;
 
+        emit!(ClaimWindowUpdatedEvent {
+            distributor: distributor.key(),
+            claim_start_ts,
+            claim_end_ts,
+        });
+
         Ok(())
     }
 
@@ -120,205 +283,237 @@ pub mod merkle_distributor {
         distributor.immediate_claim_percentage = immediate_claim_percentage;
         distributor.later_claim_offset_seconds = later_claim_offset_seconds;
 
+        emit!(ClaimPercentagesUpdatedEvent {
+            distributor: distributor.key(),
+            immediate_claim_percentage,
+            later_claim_offset_seconds,
+        });
+
         Ok(())
     }
 
-    /// Claims tokens from the [MerkleDistributor].
-    #[allow(deprecated)]
-    pub fn claim(ctx: Context<Claim>, index: u64, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
-        let distributor = &ctx.accounts.distributor;
-        require!(
-            distributor.stake_claim_only == false,
-            ErrorCode::MustClaimDirectToStake
-        );
-        let now = Clock::get()?.unix_timestamp as u64;
-        require!(
-            now >= distributor.claim_start_ts && now <= distributor.claim_end_ts,
-            ErrorCode::OutsideClaimWindow
-        );
-
-        let claim_status = &mut ctx.accounts.claim_status;
-        require!(
-            claim_status.claimed_amount < amount,
-            ErrorCode::NoClaimableAmount
-        );
+    /// Sets how the early claim ramp applies to [merkle_distributor::claim_stake].
+    pub fn update_distributor_stake_claim_scaling(
+        ctx: Context<UpdateDistributor>,
+        stake_claim_scaling: StakeClaimScaling,
+        stake_immediate_claim_percentage: u64,
+    ) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        assert!(stake_immediate_claim_percentage <= PERCENT_100);
+        distributor.stake_claim_scaling = stake_claim_scaling;
+        distributor.stake_immediate_claim_percentage = stake_immediate_claim_percentage;
+
+        emit!(StakeClaimScalingUpdatedEvent {
+            distributor: distributor.key(),
+            stake_claim_scaling,
+            stake_immediate_claim_percentage,
+        });
 
-        let claimant_account = &ctx.accounts.claimant;
+        Ok(())
+    }
 
-        // Check whether payer is the admin or the claimant
-        if (ctx.accounts.payer.key() != claimant_account.key())
-            && (ctx.accounts.payer.key() != distributor.admin_auth)
-        {
-            return Err(ErrorCode::Unauthorized)?;
-        }
+    /// Sets the grace period after `claim_end_ts` during which late claims still go through, at
+    /// `grace_claim_percentage` of the claim amount. Sweeping the vault waits until it is over.
+    pub fn update_distributor_grace_period(
+        ctx: Context<UpdateDistributor>,
+        grace_period_seconds: u64,
+        grace_claim_percentage: u64,
+    ) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        assert!(grace_claim_percentage <= PERCENT_100);
+        distributor.grace_period_seconds = grace_period_seconds;
+        distributor.grace_claim_percentage = grace_claim_percentage;
+
+        emit!(GracePeriodUpdatedEvent {
+            distributor: distributor.key(),
+            grace_period_seconds,
+            grace_claim_percentage,
+        });
 
-        require!(claimant_account.is_signer, ErrorCode::Unauthorized);
-        // Verify the merkle proof.
-        let node = anchor_lang::solana_program::keccak::hashv(&[
-            &index.to_le_bytes(),
-            &claimant_account.key().to_bytes(),
-            &amount.to_le_bytes(),
-        ]);
-        require!(
-            merkle_proof::verify(proof, distributor.root, node.0),
-            ErrorCode::InvalidProof
-        );
+        Ok(())
+    }
 
-        let claim_amount = amount.checked_sub(claim_status.claimed_amount).unwrap();
+    /// Sets the rate limits of claims, a circuit breaker against a bad root or compromised admin
+    /// draining the vault. Claims that would transfer more than `max_claim_per_window` in total,
+    /// or `max_claimant_claim_per_window` for a single claimant, within a window of
+    /// `rate_limit_window_seconds` are capped to what the window still allows, or fail with
+    /// [ErrorCode::RateLimited] if it allows nothing more; [merkle_distributor::claim_many] and
+    /// [merkle_distributor::distribute_batch] skip them with a [RateLimitTrippedEvent] instead.
+    /// Zero disables a limit.
+    pub fn update_distributor_rate_limits(
+        ctx: Context<UpdateDistributor>,
+        rate_limit_window_seconds: u64,
+        max_claim_per_window: u64,
+        max_claimant_claim_per_window: u64,
+    ) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.rate_limit_window_seconds = rate_limit_window_seconds;
+        distributor.max_claim_per_window = max_claim_per_window;
+        distributor.max_claimant_claim_per_window = max_claimant_claim_per_window;
+
+        emit!(RateLimitsUpdatedEvent {
+            distributor: distributor.key(),
+            rate_limit_window_seconds,
+            max_claim_per_window,
+            max_claimant_claim_per_window,
+        });
 
-        // Mark it claimed and send the tokens.
-        claim_status.claimed_amount = amount;
-        let clock = Clock::get()?;
-        claim_status.claimed_at = clock.unix_timestamp;
-        claim_status.claimant = claimant_account.key();
+        Ok(())
+    }
 
-        let seeds = [
-            b"MerkleDistributor".as_ref(),
-            &distributor.base.to_bytes(),
-            &[ctx.accounts.distributor.bump],
-        ];
+    /// Sets the program screening claimants, or disables screening if `screening_program` is the
+    /// default pubkey. See [claim::check_screening].
+    pub fn update_distributor_screening_program(
+        ctx: Context<UpdateDistributor>,
+        screening_program: Pubkey,
+    ) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.screening_program = screening_program;
 
-        let ata = get_associated_token_address(&ctx.accounts.distributor.key(), &distributor.mint);
-        require!(
-            ata == ctx.accounts.from.key(),
-            ErrorCode::InvalidDistributorTokenAccount
-        );
+        emit!(ScreeningProgramUpdatedEvent {
+            distributor: distributor.key(),
+            screening_program,
+        });
 
-        let transfer_amount =
-            if now < distributor.claim_start_ts + distributor.later_claim_offset_seconds {
-                let percent_to_get = get_time_scaled_percentage(
-                    distributor.immediate_claim_percentage,
-                    now.checked_sub(distributor.claim_start_ts).unwrap(),
-                    distributor.later_claim_offset_seconds,
-                );
-                get_percentage(claim_amount, percent_to_get)
-            } else {
-                claim_amount
-            };
+        Ok(())
+    }
 
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::Transfer {
-                    from: ctx.accounts.from.to_account_info(),
-                    to: ctx.accounts.to.to_account_info(),
-                    authority: ctx.accounts.distributor.to_account_info(),
-                },
-            )
-            .with_signer(&[&seeds[..]]),
-            transfer_amount,
+    /// Claims tokens from the [MerkleDistributor].
+    /// `terms` must be set if and only if the distributor uses [LeafVersion::V2] leaves.
+    ///
+    /// Like every instruction the claimant signs, the claim is authorized by the claimant's
+    /// signature, so it may be paid for by another account. This lets a PDA claim through a CPI
+    /// signed with `invoke_signed`, with a wallet paying for the claim status.
+    pub fn claim(
+        ctx: Context<Claim>,
+        index: u64,
+        amount: u64,
+        terms: Option<LeafTerms>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        claim::check_screening(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claimant.key(),
+            ctx.accounts.screening_flag.as_deref(),
         )?;
-
-        let distributor = &mut ctx.accounts.distributor;
-
-        distributor.total_amount_claimed = distributor
-            .total_amount_claimed
-            .checked_add(claim_amount)
-            .unwrap();
-        require!(
-            distributor.total_amount_claimed <= distributor.max_total_claim,
-            ErrorCode::ExceededMaxClaim
-        );
-
-        distributor.num_nodes_claimed = distributor.num_nodes_claimed.checked_add(1).unwrap();
-        require!(
-            distributor.num_nodes_claimed <= distributor.max_num_nodes,
-            ErrorCode::ExceededMaxNumNodes
+        let clock = Clock::get()?;
+        let claimant = claim::rebound_leaf_claimant(
+            ctx.accounts.claimant.key(),
+            ctx.accounts.rebind.as_deref(),
         );
+        if claimant != ctx.accounts.claimant.key() {
+            // A rebound leaf stays subject to the screening of its original claimant.
+            claim::check_screening(
+                &ctx.accounts.distributor,
+                &claimant,
+                ctx.accounts.leaf_screening_flag.as_deref(),
+            )?;
+        }
+        let mut ticket = ClaimTicket::validate(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claim_status,
+            ClaimLeaf {
+                index,
+                claimant,
+                amount,
+                terms,
+            },
+            LeafProof::Path(proof),
+            claimant,
+            clock.unix_timestamp as u64,
+            ClaimKind::Direct,
+        )?;
+        ticket.cap_to_rate_limit(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            clock.unix_timestamp as u64,
+        )?;
+        claim::transfer_from_vault(
+            &ctx.accounts.distributor,
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ticket.transfer_amount,
+        )?;
 
-        emit!(ClaimedEvent {
-            root: distributor.root,
-            index,
-            claimant: claimant_account.key(),
-            claim_amount: claim_amount,
-        });
+        ticket.record(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            ctx.accounts.payer.key(),
+            clock.unix_timestamp,
+        )?;
+        ticket.emit(&ctx.accounts.distributor, ctx.accounts.to.key(), None);
         Ok(())
     }
 
     /// Claims tokens from the [MerkleDistributor] direct to stake.
-    #[allow(deprecated)]
     pub fn claim_stake(
         ctx: Context<ClaimStake>,
         index: u64,
         amount: u64,
+        terms: Option<LeafTerms>,
         proof: Vec<[u8; 32]>,
         zeta_stake_bit_to_use: u8,
         stake_acc_name: String,
         stake_duration_epochs: u32,
     ) -> Result<()> {
-        let distributor = &ctx.accounts.distributor;
-        let now = Clock::get()?.unix_timestamp as u64;
-        require!(
-            now >= distributor.claim_start_ts && now <= distributor.claim_end_ts,
-            ErrorCode::OutsideClaimWindow
-        );
-
-        let claim_status = &mut ctx.accounts.claim_status;
-        require!(
-            claim_status.claimed_amount < amount,
-            ErrorCode::NoClaimableAmount
-        );
-
-        let claimant_account = &ctx.accounts.claimant;
-
-        // Check whether payer is the admin or the claimant
-        if (ctx.accounts.payer.key() != claimant_account.key())
-            && (ctx.accounts.payer.key() != distributor.admin_auth)
-        {
-            return Err(ErrorCode::Unauthorized)?;
-        }
-
-        require!(claimant_account.is_signer, ErrorCode::Unauthorized);
-        // Verify the merkle proof.
-        let node = anchor_lang::solana_program::keccak::hashv(&[
-            &index.to_le_bytes(),
-            &claimant_account.key().to_bytes(),
-            &amount.to_le_bytes(),
-        ]);
-        require!(
-            merkle_proof::verify(proof, distributor.root, node.0),
-            ErrorCode::InvalidProof
-        );
-
-        let claim_amount = amount.checked_sub(claim_status.claimed_amount).unwrap();
-
-        // Mark it claimed and send the tokens.
-        claim_status.claimed_amount = amount;
+        claim::check_screening(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claimant.key(),
+            ctx.accounts.screening_flag.as_deref(),
+        )?;
         let clock = Clock::get()?;
-        claim_status.claimed_at = clock.unix_timestamp;
-        claim_status.claimant = claimant_account.key();
-
-        let seeds = [
-            b"MerkleDistributor".as_ref(),
-            &distributor.base.to_bytes(),
-            &[ctx.accounts.distributor.bump],
-        ];
-
-        let ata = get_associated_token_address(&ctx.accounts.distributor.key(), &distributor.mint);
-        require!(
-            ata == ctx.accounts.from.key(),
-            ErrorCode::InvalidDistributorTokenAccount
+        let claimant = claim::rebound_leaf_claimant(
+            ctx.accounts.claimant.key(),
+            ctx.accounts.rebind.as_deref(),
         );
-
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::Transfer {
-                    from: ctx.accounts.from.to_account_info(),
-                    to: ctx.accounts.to.to_account_info(),
-                    authority: ctx.accounts.distributor.to_account_info(),
-                },
-            )
-            .with_signer(&[&seeds[..]]),
-            claim_amount,
+        if claimant != ctx.accounts.claimant.key() {
+            claim::check_screening(
+                &ctx.accounts.distributor,
+                &claimant,
+                ctx.accounts.leaf_screening_flag.as_deref(),
+            )?;
+        }
+        let mut ticket = ClaimTicket::validate(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claim_status,
+            ClaimLeaf {
+                index,
+                claimant,
+                amount,
+                terms,
+            },
+            LeafProof::Path(proof),
+            claimant,
+            clock.unix_timestamp as u64,
+            ClaimKind::Stake,
         )?;
-
         require!(
             stake_duration_epochs <= ctx.accounts.cpi_protocol_state.max_n_epochs
                 && stake_duration_epochs
                     >= ctx.accounts.cpi_protocol_state.min_stake_duration_epochs,
             ErrorCode::InvalidStakeDuration
         );
+        require!(
+            stake_duration_epochs >= ticket.leaf.stake_duration_epochs(),
+            ErrorCode::InvalidStakeDuration
+        );
+        if ctx.accounts.distributor.stake_claim_only {
+            assert!(stake_duration_epochs >= 90)
+        }
+        ticket.cap_to_rate_limit(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            clock.unix_timestamp as u64,
+        )?;
+
+        claim::transfer_from_vault(
+            &ctx.accounts.distributor,
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ticket.transfer_amount,
+        )?;
 
         let cpi_accs = zeta_staking::cpi::accounts::Stake {
             protocol_state: ctx.accounts.cpi_protocol_state.to_account_info(),
@@ -333,124 +528,933 @@ pub mod merkle_distributor {
         };
         let cpi_ctx = CpiContext::new(ctx.accounts.zeta_staking.to_account_info(), cpi_accs);
 
-        if distributor.stake_claim_only {
-            assert!(stake_duration_epochs >= 90)
-        }
-
         zeta_staking::cpi::stake(
             cpi_ctx,
             zeta_stake_bit_to_use,
             stake_duration_epochs,
-            claim_amount,
+            ticket.transfer_amount,
             stake_acc_name,
         )?;
 
-        let distributor = &mut ctx.accounts.distributor;
+        ticket.record(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            ctx.accounts.payer.key(),
+            clock.unix_timestamp,
+        )?;
+        ticket.emit(
+            &ctx.accounts.distributor,
+            ctx.accounts.cpi_stake_account.key(),
+            Some(stake_duration_epochs),
+        );
+        Ok(())
+    }
 
-        distributor.total_amount_claimed = distributor
-            .total_amount_claimed
-            .checked_add(claim_amount)
-            .unwrap();
+    /// Claims tokens from the [MerkleDistributor] and forwards them into a program approved via
+    /// [merkle_distributor::add_claim_target], e.g. a staking, LP or vault deposit instruction.
+    ///
+    /// `ix_data` is the forwarded instruction data. It must start with the approved discriminator;
+    /// the transferred amount is written into it at the approved offset. The remaining accounts are
+    /// forwarded as the instruction's accounts, and the instruction must move the claimed tokens out
+    /// of `to`.
+    pub fn claim_into_program<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimIntoProgram<'info>>,
+        index: u64,
+        amount: u64,
+        terms: Option<LeafTerms>,
+        proof: Vec<[u8; 32]>,
+        ix_data: Vec<u8>,
+    ) -> Result<()> {
+        claim::check_screening(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claimant.key(),
+            ctx.accounts.screening_flag.as_deref(),
+        )?;
+        let clock = Clock::get()?;
+        // Forwarding into a lockup follows the staked claim policy.
+        let mut ticket = ClaimTicket::validate(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claim_status,
+            ClaimLeaf {
+                index,
+                claimant: ctx.accounts.claimant.key(),
+                amount,
+                terms,
+            },
+            LeafProof::Path(proof),
+            ctx.accounts.claimant.key(),
+            clock.unix_timestamp as u64,
+            ClaimKind::Stake,
+        )?;
+        // The stake duration of a forwarded claim cannot be checked.
         require!(
-            distributor.total_amount_claimed <= distributor.max_total_claim,
-            ErrorCode::ExceededMaxClaim
+            ticket.leaf.stake_duration_epochs() == 0,
+            ErrorCode::MustClaimDirectToStake
         );
+        ticket.cap_to_rate_limit(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            clock.unix_timestamp as u64,
+        )?;
 
-        distributor.num_nodes_claimed = distributor.num_nodes_claimed.checked_add(1).unwrap();
+        let claim_target = &ctx.accounts.claim_target;
+        let mut ix_data = ix_data;
+        let discriminator = claim_target.discriminator();
+        let amount_offset = claim_target.amount_offset as usize;
         require!(
-            distributor.num_nodes_claimed <= distributor.max_num_nodes,
-            ErrorCode::ExceededMaxNumNodes
+            ix_data.starts_with(discriminator) && ix_data.len() >= amount_offset + 8,
+            ErrorCode::InvalidClaimTargetInstruction
         );
+        ix_data[amount_offset..amount_offset + 8]
+            .copy_from_slice(&ticket.transfer_amount.to_le_bytes());
 
-        emit!(ClaimedEvent {
-            root: distributor.root,
-            index,
-            claimant: claimant_account.key(),
-            claim_amount: claim_amount,
-        });
-        Ok(())
-    }
+        let balance_before = ctx.accounts.to.amount;
 
-    pub fn admin_claim_after_expiry(ctx: Context<AdminClaimAfterExpiry>) -> Result<()> {
-        let distributor = &mut ctx.accounts.distributor;
-        let now = Clock::get()?.unix_timestamp;
+        claim::transfer_from_vault(
+            &ctx.accounts.distributor,
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ticket.transfer_amount,
+        )?;
+
+        // Forward the remaining accounts as-is; the claimant's signature carries over from this
+        // transaction, the distributor never signs the forwarded instruction.
+        let ix = Instruction {
+            program_id: claim_target.program_id,
+            accounts: ctx
+                .remaining_accounts
+                .iter()
+                .map(|acc| AccountMeta {
+                    pubkey: acc.key(),
+                    is_signer: acc.is_signer,
+                    is_writable: acc.is_writable,
+                })
+                .collect(),
+            data: ix_data,
+        };
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.target_program.to_account_info());
+        invoke(&ix, &account_infos)?;
+
+        ctx.accounts.to.reload()?;
         require!(
-            now as u64 > distributor.claim_end_ts,
-            ErrorCode::InsideClaimWindow
+            ctx.accounts.to.amount <= balance_before,
+            ErrorCode::ClaimTargetDidNotConsume
         );
 
-        let seeds = [
-            b"MerkleDistributor".as_ref(),
-            &distributor.base.to_bytes(),
-            &[ctx.accounts.distributor.bump],
-        ];
-
-        // Transfer remaining tokens in the ata to admin auth ata instead.
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::Transfer {
-                    from: ctx.accounts.from.to_account_info(),
-                    to: ctx.accounts.to.to_account_info(),
-                    authority: ctx.accounts.distributor.to_account_info(),
-                },
-            )
-            .with_signer(&[&seeds[..]]),
-            ctx.accounts.from.amount,
+        ticket.record(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            ctx.accounts.payer.key(),
+            clock.unix_timestamp,
         )?;
-
+        ticket.emit(&ctx.accounts.distributor, ctx.accounts.to.key(), None);
         Ok(())
     }
 
-    pub fn update_admin_auth(ctx: Context<UpdateAdminAuth>) -> Result<()> {
-        let distributor = &mut ctx.accounts.distributor;
-        distributor.admin_auth = ctx.accounts.new_admin_auth.key();
+    /// Registers `delegate` as allowed to claim on behalf of the signing claimant from any
+    /// [MerkleDistributor], see [merkle_distributor::claim_as_delegate]. Delegated claims are sent
+    /// to token accounts owned by `destination`, the claimant if not set. Replaces any previous
+    /// delegate.
+    pub fn set_claim_delegate(
+        ctx: Context<SetClaimDelegate>,
+        delegate: Pubkey,
+        destination: Option<Pubkey>,
+    ) -> Result<()> {
+        let claim_delegate = &mut ctx.accounts.claim_delegate;
+        claim_delegate.claimant = ctx.accounts.claimant.key();
+        claim_delegate.delegate = delegate;
+        claim_delegate.destination = destination.unwrap_or(claim_delegate.claimant);
+        claim_delegate.bump = ctx.bumps.claim_delegate;
+
+        emit!(ClaimDelegateSetEvent {
+            claimant: claim_delegate.claimant,
+            delegate,
+            destination: claim_delegate.destination,
+        });
 
         Ok(())
     }
-}
-
-/// Accounts for [merkle_distributor::new_distributor].
-#[derive(Accounts)]
-#[instruction(bump: u8)]
-pub struct NewDistributor<'info> {
-    /// Base key of the distributor.
-    pub base: Signer<'info>,
-    /// Admin key of the distributor.
-    pub admin_auth: Signer<'info>,
 
-    /// [MerkleDistributor].
-    #[account(
-    init,
-    seeds = [
-    b"MerkleDistributor".as_ref(),
-    base.key().to_bytes().as_ref(),
-    ],
-    bump,
-    payer = payer,
-    space = 8 + MerkleDistributor::LEN,
-    )]
-    pub distributor: Account<'info, MerkleDistributor>,
+    /// Removes the claimant's [ClaimDelegate], returning its rent to the claimant.
+    pub fn remove_claim_delegate(ctx: Context<RemoveClaimDelegate>) -> Result<()> {
+        emit!(ClaimDelegateRemovedEvent {
+            claimant: ctx.accounts.claimant.key(),
+            delegate: ctx.accounts.claim_delegate.delegate,
+        });
 
-    /// The mint to distribute.
-    pub mint: Account<'info, Mint>,
+        Ok(())
+    }
 
-    /// Payer to create the distributor.
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    /// Claims tokens from the [MerkleDistributor] on behalf of a claimant, signed by the delegate
+    /// the claimant registered with [merkle_distributor::set_claim_delegate]. Validated like
+    /// [merkle_distributor::claim]; the tokens go to the claimant's approved destination.
+    pub fn claim_as_delegate(
+        ctx: Context<ClaimAsDelegate>,
+        index: u64,
+        amount: u64,
+        terms: Option<LeafTerms>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        claim::check_screening(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claimant.key(),
+            ctx.accounts.screening_flag.as_deref(),
+        )?;
+        let clock = Clock::get()?;
+        let claimant = ctx.accounts.claimant.key();
+        // The tokens leave for the approved destination, which must pass screening as well.
+        let destination = ctx.accounts.claim_delegate.destination;
+        if destination != claimant {
+            claim::check_screening(
+                &ctx.accounts.distributor,
+                &destination,
+                ctx.accounts.destination_screening_flag.as_deref(),
+            )?;
+        }
+        let mut ticket = ClaimTicket::validate(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claim_status,
+            ClaimLeaf {
+                index,
+                claimant,
+                amount,
+                terms,
+            },
+            LeafProof::Path(proof),
+            // The delegate claims with the claimant's authority.
+            claimant,
+            clock.unix_timestamp as u64,
+            ClaimKind::Direct,
+        )?;
+        ticket.cap_to_rate_limit(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            clock.unix_timestamp as u64,
+        )?;
+        claim::transfer_from_vault(
+            &ctx.accounts.distributor,
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ticket.transfer_amount,
+        )?;
 
-    /// The [System] program.
-    pub system_program: Program<'info, System>,
-}
+        ticket.record(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            ctx.accounts.payer.key(),
+            clock.unix_timestamp,
+        )?;
+        ticket.emit(&ctx.accounts.distributor, ctx.accounts.to.key(), None);
+        Ok(())
+    }
 
-/// Accounts for [merkle_distributor::update_distributor_admin_auth].
-#[derive(Accounts)]
-pub struct UpdateDistributorAdminAuth<'info> {
-    /// Admin key of the distributor.
-    pub admin_auth: Signer<'info>,
+    /// Transfers the right to claim the signing claimant's leaf to `new_claimant`, e.g. when
+    /// rotating wallets. What was already claimed carries over to the leaf's own claim status at
+    /// `["ReboundClaimStatus", distributor, claimant]`, so a new wallet with a leaf of its own
+    /// keeps claiming both. It claims the rebound leaf by passing the [ClaimRebind] to
+    /// [merkle_distributor::claim], [merkle_distributor::claim_stake] or
+    /// [merkle_distributor::claim_native]; the other claim instructions fail on it with
+    /// [ErrorCode::ClaimRebound]. The original claimant can no longer claim it.
+    pub fn rebind_claim(
+        ctx: Context<RebindClaim>,
+        index: u64,
+        amount: u64,
+        terms: Option<LeafTerms>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        // A flagged claimant may not pass its leaf on to another wallet either.
+        claim::check_screening(
+            distributor,
+            &ctx.accounts.claimant.key(),
+            ctx.accounts.screening_flag.as_deref(),
+        )?;
+        let leaf = ClaimLeaf {
+            index,
+            claimant: ctx.accounts.claimant.key(),
+            amount,
+            terms,
+        };
+        require!(
+            leaf.terms.is_some() == (distributor.leaf_version == LeafVersion::V2),
+            ErrorCode::InvalidLeafTerms
+        );
+        require!(leaf.verify(distributor, proof)?, ErrorCode::InvalidProof);
+        let claim_status = &mut ctx.accounts.claim_status;
+        require!(claim_status.revoked_at == 0, ErrorCode::ClaimRevoked);
+        require!(
+            claim_status.rebound_to == Pubkey::default(),
+            ErrorCode::ClaimRebound
+        );
 
-    /// CHECK: Just have new admin not be signer
-    pub new_admin: AccountInfo<'info>,
+        let new_claimant = ctx.accounts.new_claimant.key();
+        if claim_status.num_claims == 0 {
+            claim_status.payer = ctx.accounts.payer.key();
+        }
+        claim_status.claimant = leaf.claimant;
+        claim_status.index = index;
+        claim_status.root = distributor.root;
+        claim_status.rebound_to = new_claimant;
+
+        let rebound_claim_status = &mut ctx.accounts.rebound_claim_status;
+        rebound_claim_status.claimant = leaf.claimant;
+        rebound_claim_status.claimed_at = claim_status.claimed_at;
+        rebound_claim_status.claimed_amount = claim_status.claimed_amount;
+        rebound_claim_status.index = index;
+        rebound_claim_status.root = distributor.root;
+        rebound_claim_status.num_claims = claim_status.num_claims;
+        rebound_claim_status.payer = ctx.accounts.payer.key();
+        rebound_claim_status.rate_limit_window = claim_status.rate_limit_window;
+
+        let claim_rebind = &mut ctx.accounts.claim_rebind;
+        claim_rebind.distributor = distributor.key();
+        claim_rebind.claimant = leaf.claimant;
+        claim_rebind.new_claimant = new_claimant;
+        claim_rebind.bump = ctx.bumps.claim_rebind;
+
+        emit!(ClaimReboundEvent {
+            distributor: distributor.key(),
+            claimant: leaf.claimant,
+            new_claimant,
+            index,
+        });
+
+        Ok(())
+    }
+
+    /// Claims native lamports from a `native` [MerkleDistributor] into the claimant's wallet.
+    /// Validated like [merkle_distributor::claim], with the same window, ramp and caps.
+    pub fn claim_native(
+        ctx: Context<ClaimNative>,
+        index: u64,
+        amount: u64,
+        terms: Option<LeafTerms>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.distributor.native,
+            ErrorCode::NotNativeDistributor
+        );
+        claim::check_screening(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claimant.key(),
+            ctx.accounts.screening_flag.as_deref(),
+        )?;
+        let clock = Clock::get()?;
+        let claimant = claim::rebound_leaf_claimant(
+            ctx.accounts.claimant.key(),
+            ctx.accounts.rebind.as_deref(),
+        );
+        if claimant != ctx.accounts.claimant.key() {
+            // A rebound leaf stays subject to the screening of its original claimant.
+            claim::check_screening(
+                &ctx.accounts.distributor,
+                &claimant,
+                ctx.accounts.leaf_screening_flag.as_deref(),
+            )?;
+        }
+        let mut ticket = ClaimTicket::validate(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claim_status,
+            ClaimLeaf {
+                index,
+                claimant,
+                amount,
+                terms,
+            },
+            LeafProof::Path(proof),
+            claimant,
+            clock.unix_timestamp as u64,
+            ClaimKind::Direct,
+        )?;
+        ticket.cap_to_rate_limit(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            clock.unix_timestamp as u64,
+        )?;
+        claim::transfer_native(
+            &ctx.accounts.distributor,
+            ctx.accounts.claimant.to_account_info(),
+            ticket.transfer_amount,
+        )?;
+
+        ticket.record(
+            &mut ctx.accounts.distributor,
+            &mut ctx.accounts.claim_status,
+            ctx.accounts.payer.key(),
+            clock.unix_timestamp,
+        )?;
+        ticket.emit(&ctx.accounts.distributor, ctx.accounts.claimant.key(), None);
+        Ok(())
+    }
+
+    /// Claims from several [MerkleDistributor]s in one instruction, authorized by the claimant's
+    /// signature like [merkle_distributor::claim].
+    ///
+    /// Each entry takes [CLAIM_MANY_ACCOUNTS] remaining accounts, in order: the distributor, the
+    /// claimant's [ClaimStatus] for it, the distributor ATA and the claimant's token account.
+    /// Proofs are concatenated in `proofs`, each entry consuming `proof_len` of them.
+    pub fn claim_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimMany<'info>>,
+        entries: Vec<ClaimManyEntry>,
+        proofs: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() == entries.len() * CLAIM_MANY_ACCOUNTS,
+            ErrorCode::InvalidRemainingAccounts
+        );
+
+        let clock = Clock::get()?;
+        let claimant = ctx.accounts.claimant.key();
+        let payer = ctx.accounts.payer.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let mut proofs = proofs.into_iter();
+
+        for (entry, accounts) in entries
+            .iter()
+            .zip(ctx.remaining_accounts.chunks(CLAIM_MANY_ACCOUNTS))
+        {
+            let proof: Vec<[u8; 32]> = proofs.by_ref().take(entry.proof_len as usize).collect();
+            require!(
+                proof.len() == entry.proof_len as usize,
+                ErrorCode::InvalidProof
+            );
+
+            let mut distributor: Account<MerkleDistributor> = Account::try_from(&accounts[0])?;
+            require!(
+                distributor.screening_program == Pubkey::default(),
+                ErrorCode::ScreenedDistributor
+            );
+            let mut claim_status = claim::load_or_init_claim_status(
+                &accounts[1],
+                &distributor.key(),
+                &claimant,
+                &payer,
+                &system_program,
+            )?;
+            let from: Account<TokenAccount> = Account::try_from(&accounts[2])?;
+            let to: Account<TokenAccount> = Account::try_from(&accounts[3])?;
+            claim::check_vault(&distributor.key(), &distributor, &from.key())?;
+            claim::check_destination(&distributor, &to, &claimant)?;
+
+            let ticket = ClaimTicket::validate(
+                &distributor,
+                &claim_status,
+                ClaimLeaf {
+                    index: entry.index,
+                    claimant,
+                    amount: entry.amount,
+                    terms: entry.terms,
+                },
+                LeafProof::Path(proof),
+                claimant,
+                clock.unix_timestamp as u64,
+                ClaimKind::Direct,
+            )?;
+            if let Some(limit) = ticket.rate_limit(
+                &mut distributor,
+                &mut claim_status,
+                clock.unix_timestamp as u64,
+            ) {
+                ticket.emit_rate_limited(&distributor, limit);
+                continue;
+            }
+
+            claim::transfer_from_vault(
+                &distributor,
+                from.to_account_info(),
+                to.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ticket.transfer_amount,
+            )?;
+
+            ticket.record(
+                &mut distributor,
+                &mut claim_status,
+                payer.key(),
+                clock.unix_timestamp,
+            )?;
+            ticket.emit(&distributor, to.key(), None);
+
+            // Persist before the next entry, which may load the same accounts.
+            distributor.exit(&crate::ID)?;
+            claim_status.exit(&crate::ID)?;
+        }
+        require!(proofs.next().is_none(), ErrorCode::InvalidProof);
+
+        Ok(())
+    }
+
+    /// Pushes tokens to many claimants on their behalf. Only callable by the admin, whose signature
+    /// authorizes the claims in place of the claimants'.
+    ///
+    /// Leaves are validated like [merkle_distributor::claim] at the time of the push, and proven
+    /// together by a single multiproof, so `entries` must be in tree order. Each entry takes
+    /// [DISTRIBUTE_BATCH_ACCOUNTS] remaining accounts, in order: the claimant's [ClaimStatus] and
+    /// the claimant's token account.
+    pub fn distribute_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeBatch<'info>>,
+        entries: Vec<DistributeBatchEntry>,
+        proof: Vec<[u8; 32]>,
+        proof_flags: Vec<ProofFlag>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() == entries.len() * DISTRIBUTE_BATCH_ACCOUNTS,
+            ErrorCode::InvalidRemainingAccounts
+        );
+        let leaves: Vec<ClaimLeaf> = entries
+            .iter()
+            .map(|entry| ClaimLeaf {
+                index: entry.index,
+                claimant: entry.claimant,
+                amount: entry.amount,
+                terms: entry.terms,
+            })
+            .collect();
+        let distributor = &ctx.accounts.distributor;
+        require!(
+            distributor.screening_program == Pubkey::default(),
+            ErrorCode::ScreenedDistributor
+        );
+        let hashes = leaves
+            .iter()
+            .map(|leaf| leaf.hash(distributor))
+            .collect::<Result<Vec<[u8; 32]>>>()?;
+        require!(
+            merkle_proof::verify_multi(
+                &hashes,
+                &proof,
+                &proof_flags,
+                distributor.root,
+                distributor.hash_scheme,
+                &distributor.hash_function
+            )?,
+            ErrorCode::InvalidProof
+        );
+
+        let clock = Clock::get()?;
+        let admin_auth = ctx.accounts.admin_auth.key();
+        let payer = ctx.accounts.payer.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        for (leaf, accounts) in leaves
+            .into_iter()
+            .zip(ctx.remaining_accounts.chunks(DISTRIBUTE_BATCH_ACCOUNTS))
+        {
+            let mut claim_status = claim::load_or_init_claim_status(
+                &accounts[0],
+                &ctx.accounts.distributor.key(),
+                &leaf.claimant,
+                &payer,
+                &system_program,
+            )?;
+            let to: Account<TokenAccount> = Account::try_from(&accounts[1])?;
+            claim::check_destination(&ctx.accounts.distributor, &to, &leaf.claimant)?;
+
+            let ticket = ClaimTicket::validate(
+                &ctx.accounts.distributor,
+                &claim_status,
+                leaf,
+                LeafProof::Multi,
+                admin_auth,
+                clock.unix_timestamp as u64,
+                ClaimKind::Direct,
+            )?;
+            if let Some(limit) = ticket.rate_limit(
+                &mut ctx.accounts.distributor,
+                &mut claim_status,
+                clock.unix_timestamp as u64,
+            ) {
+                ticket.emit_rate_limited(&ctx.accounts.distributor, limit);
+                continue;
+            }
+
+            claim::transfer_from_vault(
+                &ctx.accounts.distributor,
+                ctx.accounts.from.to_account_info(),
+                to.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ticket.transfer_amount,
+            )?;
+
+            ticket.record(
+                &mut ctx.accounts.distributor,
+                &mut claim_status,
+                payer.key(),
+                clock.unix_timestamp,
+            )?;
+            ticket.emit(&ctx.accounts.distributor, to.key(), None);
+
+            // Persist before the next entry, which may load the same claim status.
+            claim_status.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    /// Approves `target_program` as a destination for [merkle_distributor::claim_into_program].
+    pub fn add_claim_target(
+        ctx: Context<AddClaimTarget>,
+        ix_discriminator: [u8; 8],
+        discriminator_len: u8,
+        amount_offset: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.target_program.key() != crate::ID,
+            ErrorCode::InvalidClaimTarget
+        );
+        require!(
+            discriminator_len as usize <= ix_discriminator.len()
+                && amount_offset >= discriminator_len as u16,
+            ErrorCode::InvalidClaimTargetInstruction
+        );
+
+        let claim_target = &mut ctx.accounts.claim_target;
+        claim_target.distributor = ctx.accounts.distributor.key();
+        claim_target.program_id = ctx.accounts.target_program.key();
+        claim_target.ix_discriminator = ix_discriminator;
+        claim_target.discriminator_len = discriminator_len;
+        claim_target.amount_offset = amount_offset;
+        claim_target.bump = ctx.bumps.claim_target;
+
+        emit!(ClaimTargetAddedEvent {
+            distributor: claim_target.distributor,
+            program_id: claim_target.program_id,
+            ix_discriminator,
+            discriminator_len,
+            amount_offset,
+        });
+
+        Ok(())
+    }
+
+    /// Revokes the unclaimed part of `claimant`'s leaf, e.g. for a sanctioned address or a departed
+    /// employee. The leaf can no longer be claimed and `max_total_claim` shrinks by the revoked
+    /// amount. The revoked amount is returned to `to` if it is passed, otherwise it stays in the
+    /// vault for [merkle_distributor::admin_sweep]. A `native` distributor always keeps it, for
+    /// [merkle_distributor::admin_sweep_native]. Revoking a rebound leaf also revokes the claim
+    /// status it is claimed through by the wallet it was rebound to.
+    pub fn revoke_claim(
+        ctx: Context<RevokeClaim>,
+        index: u64,
+        amount: u64,
+        terms: Option<LeafTerms>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        let leaf = ClaimLeaf {
+            index,
+            claimant: ctx.accounts.claimant.key(),
+            amount,
+            terms,
+        };
+        require!(
+            leaf.terms.is_some() == (distributor.leaf_version == LeafVersion::V2),
+            ErrorCode::InvalidLeafTerms
+        );
+        require!(leaf.verify(distributor, proof)?, ErrorCode::InvalidProof);
+        require!(
+            ctx.accounts.claim_status.revoked_at == 0,
+            ErrorCode::ClaimRevoked
+        );
+        // A rebound leaf is claimed through its own rebound claim status, revoked along with it.
+        let rebound = ctx.accounts.claim_status.rebound_to != Pubkey::default();
+        let claimed_amount = if rebound {
+            let rebound_claim_status = ctx
+                .accounts
+                .rebound_claim_status
+                .as_ref()
+                .ok_or(ErrorCode::ClaimRebound)?;
+            require!(
+                rebound_claim_status.revoked_at == 0,
+                ErrorCode::ClaimRevoked
+            );
+            rebound_claim_status.claimed_amount
+        } else {
+            ctx.accounts.claim_status.claimed_amount
+        };
+        let revoked_amount = amount.saturating_sub(claimed_amount);
+        require!(revoked_amount > 0, ErrorCode::NoClaimableAmount);
+
+        if let Some(to) = &ctx.accounts.to {
+            let from = ctx
+                .accounts
+                .from
+                .as_ref()
+                .ok_or(ErrorCode::InvalidDistributorTokenAccount)?;
+            claim::transfer_from_vault(
+                distributor,
+                from.to_account_info(),
+                to.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                revoked_amount,
+            )?;
+        }
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.max_total_claim = distributor.max_total_claim.saturating_sub(revoked_amount);
+
+        let claim_status = &mut ctx.accounts.claim_status;
+        if claim_status.num_claims == 0 {
+            claim_status.payer = ctx.accounts.payer.key();
+        }
+        claim_status.claimant = leaf.claimant;
+        claim_status.index = index;
+        claim_status.root = distributor.root;
+        claim_status.revoked_at = Clock::get()?.unix_timestamp;
+        if let Some(rebound_claim_status) = ctx
+            .accounts
+            .rebound_claim_status
+            .as_mut()
+            .filter(|_| rebound)
+        {
+            rebound_claim_status.revoked_at = claim_status.revoked_at;
+        }
+
+        emit!(ClaimRevokedEvent {
+            distributor: distributor.key(),
+            claimant: leaf.claimant,
+            index,
+            revoked_amount,
+            returned: ctx.accounts.to.is_some(),
+        });
+
+        Ok(())
+    }
+
+    /// Revokes a [ClaimTarget], returning its rent to the admin.
+    pub fn remove_claim_target(ctx: Context<RemoveClaimTarget>) -> Result<()> {
+        emit!(ClaimTargetRemovedEvent {
+            distributor: ctx.accounts.distributor.key(),
+            program_id: ctx.accounts.claim_target.program_id,
+        });
+
+        Ok(())
+    }
+
+    /// Sweeps `amount` out of the vault. Before the claim window is over, only tokens above what
+    /// can still be claimed can be swept, e.g. those forfeited to the early claim ramp.
+    pub fn admin_sweep(ctx: Context<AdminClaimAfterExpiry>, amount: u64) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        let now = Clock::get()?.unix_timestamp as u64;
+        require!(
+            amount <= distributor.get_sweepable_amount(ctx.accounts.from.amount, now),
+            ErrorCode::ExceededSweepableAmount
+        );
+
+        claim::transfer_from_vault(
+            distributor,
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(SweptEvent {
+            distributor: distributor.key(),
+            to: ctx.accounts.to.key(),
+            amount,
+            permissionless: false,
+        });
+
+        Ok(())
+    }
+
+    /// Sweeps `amount` lamports out of a `native` [MerkleDistributor] to the admin, with the same
+    /// limits as [merkle_distributor::admin_sweep].
+    pub fn admin_sweep_native(ctx: Context<AdminSweepNative>, amount: u64) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        require!(distributor.native, ErrorCode::NotNativeDistributor);
+        let now = Clock::get()?.unix_timestamp as u64;
+        let vault_amount = claim::native_vault_amount(&distributor.to_account_info())?;
+        require!(
+            amount <= distributor.get_sweepable_amount(vault_amount, now),
+            ErrorCode::ExceededSweepableAmount
+        );
+
+        claim::transfer_native(
+            distributor,
+            ctx.accounts.admin_auth.to_account_info(),
+            amount,
+        )?;
+
+        emit!(SweptEvent {
+            distributor: distributor.key(),
+            to: ctx.accounts.admin_auth.key(),
+            amount,
+            permissionless: false,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the token account [merkle_distributor::sweep_after_expiry] sends the vault to, or
+    /// unsets it if `sweep_recipient` is not passed. A `native` distributor has no token vault to
+    /// sweep and takes no recipient; its admin sweeps with [merkle_distributor::admin_sweep_native].
+    pub fn set_sweep_recipient(ctx: Context<SetSweepRecipient>) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        require!(
+            !distributor.native || ctx.accounts.sweep_recipient.is_none(),
+            ErrorCode::NativeSweepRecipient
+        );
+        distributor.sweep_recipient = ctx
+            .accounts
+            .sweep_recipient
+            .as_ref()
+            .map_or(Pubkey::default(), |recipient| recipient.key());
+
+        emit!(SweepRecipientUpdatedEvent {
+            distributor: distributor.key(),
+            sweep_recipient: distributor.sweep_recipient,
+        });
+
+        Ok(())
+    }
+
+    /// Sends the whole vault to the distributor's `sweep_recipient` once the claim window and its
+    /// grace period are over. Anyone can call this.
+    pub fn sweep_after_expiry(ctx: Context<SweepAfterExpiry>) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now as u64 > distributor.get_claim_deadline(),
+            ErrorCode::InsideClaimWindow
+        );
+
+        claim::transfer_from_vault(
+            distributor,
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.from.amount,
+        )?;
+
+        emit!(SweptEvent {
+            distributor: distributor.key(),
+            to: ctx.accounts.to.key(),
+            amount: ctx.accounts.from.amount,
+            permissionless: true,
+        });
+
+        Ok(())
+    }
+
+    pub fn admin_claim_after_expiry(ctx: Context<AdminClaimAfterExpiry>) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now as u64 > distributor.get_claim_deadline(),
+            ErrorCode::InsideClaimWindow
+        );
+
+        // Transfer remaining tokens in the ata to admin auth ata instead.
+        claim::transfer_from_vault(
+            distributor,
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.from.amount,
+        )?;
+
+        emit!(AdminClaimedAfterExpiryEvent {
+            distributor: distributor.key(),
+            to: ctx.accounts.to.key(),
+            amount: ctx.accounts.from.amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_admin_auth(ctx: Context<UpdateAdminAuth>) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        emit!(AdminAuthUpdatedEvent {
+            distributor: distributor.key(),
+            old_admin_auth: distributor.admin_auth,
+            new_admin_auth: ctx.accounts.new_admin_auth.key(),
+        });
+        distributor.admin_auth = ctx.accounts.new_admin_auth.key();
+
+        Ok(())
+    }
+}
+
+/// Accounts for [merkle_distributor::new_distributor].
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct NewDistributor<'info> {
+    /// Base key of the distributor.
+    pub base: Signer<'info>,
+    /// Admin key of the distributor.
+    pub admin_auth: Signer<'info>,
+
+    /// [MerkleDistributor].
+    #[account(
+    init,
+    seeds = [
+    b"MerkleDistributor".as_ref(),
+    base.key().to_bytes().as_ref(),
+    ],
+    bump,
+    payer = payer,
+    space = 8 + MerkleDistributor::LEN,
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// The mint to distribute.
+    pub mint: Account<'info, Mint>,
+
+    /// Payer to create the distributor.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [merkle_distributor::migrate_claim_status].
+#[derive(Accounts)]
+pub struct MigrateClaimStatus<'info> {
+    /// CHECK: [ClaimStatus] under an older layout, checked in the instruction.
+    #[account(mut, owner = crate::ID)]
+    pub claim_status: UncheckedAccount<'info>,
+
+    /// Payer of the rent for the added space.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [merkle_distributor::migrate_distributor].
+#[derive(Accounts)]
+pub struct MigrateDistributor<'info> {
+    /// Admin key of the distributor.
+    pub admin_auth: Signer<'info>,
+
+    /// CHECK: [MerkleDistributor] under any layout version, checked in the instruction.
+    #[account(mut, owner = crate::ID)]
+    pub distributor: UncheckedAccount<'info>,
+
+    /// Payer of the rent for the added space.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [merkle_distributor::update_distributor_admin_auth].
+#[derive(Accounts)]
+pub struct UpdateDistributorAdminAuth<'info> {
+    /// Admin key of the distributor.
+    pub admin_auth: Signer<'info>,
+
+    /// CHECK: Just have new admin not be signer
+    pub new_admin: AccountInfo<'info>,
 
     #[account(mut, has_one = admin_auth @ ErrorCode::DistributorAdminMismatch)]
     pub distributor: Account<'info, MerkleDistributor>,
@@ -473,13 +1477,13 @@ pub struct Claim<'info> {
     #[account(mut)]
     pub distributor: Account<'info, MerkleDistributor>,
 
-    /// Status of the claim.
+    /// Status of the claim, the leaf's own status if it was rebound to `claimant`.
     #[account(
     init_if_needed,
     seeds = [
-    b"ClaimStatus".as_ref(),
+    claim::claim_status_seed(rebind.as_deref()),
     distributor.key().to_bytes().as_ref(),
-    claimant.key().to_bytes().as_ref()
+    claim::rebound_leaf_claimant(claimant.key(), rebind.as_deref()).as_ref()
     ],
     bump,
     payer = payer,
@@ -488,18 +1492,22 @@ pub struct Claim<'info> {
     pub claim_status: Account<'info, ClaimStatus>,
 
     /// Distributor ATA containing the tokens to distribute.
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
     pub from: Account<'info, TokenAccount>,
 
     /// Account to send the claimed tokens to.
-    #[account(mut)]
+    #[account(mut, token::mint = distributor.mint)]
     pub to: Account<'info, TokenAccount>,
 
     /// Who is claiming the tokens.
     #[account(address = to.owner @ ErrorCode::OwnerMismatch)]
     pub claimant: Signer<'info>,
 
-    /// Payer of the claim.
+    /// Payer of the claim, e.g. a wallet paying for a PDA claimant.
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -508,6 +1516,23 @@ pub struct Claim<'info> {
 
     /// SPL [Token] program.
     pub token_program: Program<'info, Token>,
+
+    /// CHECK: The claimant's flag account of the distributor's screening program, required if it
+    /// has one. Checked by [claim::check_screening].
+    pub screening_flag: Option<UncheckedAccount<'info>>,
+    /// The [ClaimRebind] of a leaf rebound to `claimant`, see [merkle_distributor::rebind_claim].
+    #[account(
+    seeds = [
+    b"ClaimRebind".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump = rebind.bump,
+    )]
+    pub rebind: Option<Account<'info, ClaimRebind>>,
+    /// CHECK: The flag account of the original claimant of a rebound leaf, required if the
+    /// distributor has a screening program. Checked by [claim::check_screening].
+    pub leaf_screening_flag: Option<UncheckedAccount<'info>>,
 }
 
 /// [merkle_distributor::claim_stake] accounts.
@@ -517,13 +1542,13 @@ pub struct ClaimStake<'info> {
     #[account(mut)]
     pub distributor: Account<'info, MerkleDistributor>,
 
-    /// Status of the claim.
+    /// Status of the claim, the leaf's own status if it was rebound to `claimant`.
     #[account(
     init_if_needed,
     seeds = [
-    b"ClaimStatus".as_ref(),
+    claim::claim_status_seed(rebind.as_deref()),
     distributor.key().to_bytes().as_ref(),
-    claimant.key().to_bytes().as_ref()
+    claim::rebound_leaf_claimant(claimant.key(), rebind.as_deref()).as_ref()
     ],
     bump,
     payer = payer,
@@ -532,11 +1557,15 @@ pub struct ClaimStake<'info> {
     pub claim_status: Account<'info, ClaimStatus>,
 
     /// Distributor ATA containing the tokens to distribute.
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
     pub from: Account<'info, TokenAccount>,
 
     /// Account to send the claimed tokens to.
-    #[account(mut)]
+    #[account(mut, token::mint = distributor.mint)]
     pub to: Account<'info, TokenAccount>,
 
     /// Zeta staking account
@@ -554,18 +1583,468 @@ pub struct ClaimStake<'info> {
     #[account(mut)]
     pub cpi_stake_account: AccountInfo<'info>,
 
-    /// CHECK: Zeta staking stake vault PDA will be uninitialized and checked in the CPI
-    #[account(mut)]
-    pub cpi_stake_vault: AccountInfo<'info>,
+    /// CHECK: Zeta staking stake vault PDA will be uninitialized and checked in the CPI
+    #[account(mut)]
+    pub cpi_stake_vault: AccountInfo<'info>,
+
+    /// Zeta mint, address is checked in the CPI
+    pub zeta_mint: Account<'info, Mint>,
+
+    /// Who is claiming the tokens.
+    #[account(address = to.owner @ ErrorCode::OwnerMismatch)]
+    pub claimant: Signer<'info>,
+
+    /// Payer of the claim.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: The claimant's flag account of the distributor's screening program, required if it
+    /// has one. Checked by [claim::check_screening].
+    pub screening_flag: Option<UncheckedAccount<'info>>,
+    /// The [ClaimRebind] of a leaf rebound to `claimant`, see [merkle_distributor::rebind_claim].
+    #[account(
+    seeds = [
+    b"ClaimRebind".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump = rebind.bump,
+    )]
+    pub rebind: Option<Account<'info, ClaimRebind>>,
+    /// CHECK: The flag account of the original claimant of a rebound leaf, required if the
+    /// distributor has a screening program. Checked by [claim::check_screening].
+    pub leaf_screening_flag: Option<UncheckedAccount<'info>>,
+}
+
+/// [merkle_distributor::claim_many] accounts.
+#[derive(Accounts)]
+pub struct ClaimMany<'info> {
+    /// Who is claiming the tokens.
+    pub claimant: Signer<'info>,
+
+    /// Payer of the claims.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+}
+
+/// [merkle_distributor::distribute_batch] accounts.
+#[derive(Accounts)]
+pub struct DistributeBatch<'info> {
+    /// Admin key of the distributor.
+    pub admin_auth: Signer<'info>,
+
+    /// The [MerkleDistributor].
+    #[account(mut, has_one = admin_auth @ ErrorCode::DistributorAdminMismatch)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// Payer of the claim statuses.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+}
+
+/// [merkle_distributor::claim_into_program] accounts.
+#[derive(Accounts)]
+pub struct ClaimIntoProgram<'info> {
+    /// The [MerkleDistributor].
+    #[account(mut)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Status of the claim.
+    #[account(
+    init_if_needed,
+    seeds = [
+    b"ClaimStatus".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimStatus::LEN,
+    )]
+    pub claim_status: Account<'info, ClaimStatus>,
+
+    /// The approved [ClaimTarget].
+    #[account(
+    seeds = [
+    b"ClaimTarget".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    target_program.key().to_bytes().as_ref()
+    ],
+    bump = claim_target.bump,
+    has_one = distributor,
+    )]
+    pub claim_target: Account<'info, ClaimTarget>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// Account the claimed tokens pass through on their way into the target program.
+    #[account(mut, token::mint = distributor.mint)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// CHECK: Must match the approved [ClaimTarget] program.
+    #[account(executable, address = claim_target.program_id @ ErrorCode::InvalidClaimTarget)]
+    pub target_program: UncheckedAccount<'info>,
+
+    /// Who is claiming the tokens.
+    #[account(address = to.owner @ ErrorCode::OwnerMismatch)]
+    pub claimant: Signer<'info>,
+
+    /// Payer of the claim.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: The claimant's flag account of the distributor's screening program, required if it
+    /// has one. Checked by [claim::check_screening].
+    pub screening_flag: Option<UncheckedAccount<'info>>,
+}
+
+/// Accounts for [merkle_distributor::set_claim_delegate].
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    /// The claimant registering the delegate, paying for the [ClaimDelegate].
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// [ClaimDelegate].
+    #[account(
+    init_if_needed,
+    seeds = [
+    b"ClaimDelegate".as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = claimant,
+    space = 8 + ClaimDelegate::LEN,
+    )]
+    pub claim_delegate: Account<'info, ClaimDelegate>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [merkle_distributor::remove_claim_delegate].
+#[derive(Accounts)]
+pub struct RemoveClaimDelegate<'info> {
+    /// The claimant that registered the delegate.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// [ClaimDelegate] to close.
+    #[account(
+    mut,
+    seeds = [
+    b"ClaimDelegate".as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump = claim_delegate.bump,
+    close = claimant,
+    )]
+    pub claim_delegate: Account<'info, ClaimDelegate>,
+}
+
+/// [merkle_distributor::claim_as_delegate] accounts.
+#[derive(Accounts)]
+pub struct ClaimAsDelegate<'info> {
+    /// The [MerkleDistributor].
+    #[account(mut)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// The claimant's [ClaimDelegate].
+    #[account(
+    seeds = [
+    b"ClaimDelegate".as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump = claim_delegate.bump,
+    has_one = delegate @ ErrorCode::Unauthorized,
+    )]
+    pub claim_delegate: Account<'info, ClaimDelegate>,
+
+    /// Status of the claim.
+    #[account(
+    init_if_needed,
+    seeds = [
+    b"ClaimStatus".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimStatus::LEN,
+    )]
+    pub claim_status: Account<'info, ClaimStatus>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// Account to send the claimed tokens to, owned by the claimant's approved destination.
+    #[account(
+        mut,
+        token::mint = distributor.mint,
+        constraint = to.owner == claim_delegate.destination @ ErrorCode::OwnerMismatch,
+    )]
+    pub to: Account<'info, TokenAccount>,
+
+    /// CHECK: The claimant being claimed for, bound by the seeds of `claim_delegate`.
+    pub claimant: UncheckedAccount<'info>,
+
+    /// The delegate registered by the claimant.
+    pub delegate: Signer<'info>,
+
+    /// Payer of the claim.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: The claimant's flag account of the distributor's screening program, required if it
+    /// has one. Checked by [claim::check_screening].
+    pub screening_flag: Option<UncheckedAccount<'info>>,
+    /// CHECK: The flag account of the approved destination, required if the distributor has a
+    /// screening program and the destination is not the claimant. Checked by
+    /// [claim::check_screening].
+    pub destination_screening_flag: Option<UncheckedAccount<'info>>,
+}
+
+/// [merkle_distributor::rebind_claim] accounts.
+#[derive(Accounts)]
+pub struct RebindClaim<'info> {
+    /// The [MerkleDistributor].
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Status of the original claimant's claim.
+    #[account(
+    init_if_needed,
+    seeds = [
+    b"ClaimStatus".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimStatus::LEN,
+    )]
+    pub claim_status: Account<'info, ClaimStatus>,
+
+    /// Status of the rebound leaf, apart from the claim status of any leaf of the new wallet's
+    /// own.
+    #[account(
+    init,
+    seeds = [
+    b"ReboundClaimStatus".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimStatus::LEN,
+    )]
+    pub rebound_claim_status: Account<'info, ClaimStatus>,
+
+    /// [ClaimRebind].
+    #[account(
+    init,
+    seeds = [
+    b"ClaimRebind".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    new_claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimRebind::LEN,
+    )]
+    pub claim_rebind: Account<'info, ClaimRebind>,
+
+    /// The claimant of the leaf.
+    pub claimant: Signer<'info>,
+
+    /// CHECK: The wallet the leaf is rebound to.
+    pub new_claimant: UncheckedAccount<'info>,
+
+    /// Payer of the new accounts.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The claimant's flag account of the distributor's screening program, required if it
+    /// has one. Checked by [claim::check_screening].
+    pub screening_flag: Option<UncheckedAccount<'info>>,
+}
+
+/// [merkle_distributor::claim_native] accounts.
+#[derive(Accounts)]
+pub struct ClaimNative<'info> {
+    /// The `native` [MerkleDistributor], holding the lamports to distribute.
+    #[account(mut)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Status of the claim, the leaf's own status if it was rebound to `claimant`.
+    #[account(
+    init_if_needed,
+    seeds = [
+    claim::claim_status_seed(rebind.as_deref()),
+    distributor.key().to_bytes().as_ref(),
+    claim::rebound_leaf_claimant(claimant.key(), rebind.as_deref()).as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimStatus::LEN,
+    )]
+    pub claim_status: Account<'info, ClaimStatus>,
+
+    /// Who is claiming, receiving the lamports.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// Payer of the claim.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The claimant's flag account of the distributor's screening program, required if it
+    /// has one. Checked by [claim::check_screening].
+    pub screening_flag: Option<UncheckedAccount<'info>>,
+    /// The [ClaimRebind] of a leaf rebound to `claimant`, see [merkle_distributor::rebind_claim].
+    #[account(
+    seeds = [
+    b"ClaimRebind".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump = rebind.bump,
+    )]
+    pub rebind: Option<Account<'info, ClaimRebind>>,
+    /// CHECK: The flag account of the original claimant of a rebound leaf, required if the
+    /// distributor has a screening program. Checked by [claim::check_screening].
+    pub leaf_screening_flag: Option<UncheckedAccount<'info>>,
+}
+
+/// Accounts for [merkle_distributor::add_claim_target].
+#[derive(Accounts)]
+pub struct AddClaimTarget<'info> {
+    /// Admin key of the distributor.
+    pub admin_auth: Signer<'info>,
+
+    #[account(has_one = admin_auth @ ErrorCode::DistributorAdminMismatch)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// [ClaimTarget].
+    #[account(
+    init,
+    seeds = [
+    b"ClaimTarget".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    target_program.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimTarget::LEN,
+    )]
+    pub claim_target: Account<'info, ClaimTarget>,
+
+    /// CHECK: The program being approved, only its key is stored.
+    #[account(executable)]
+    pub target_program: UncheckedAccount<'info>,
+
+    /// Payer to create the claim target.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// [merkle_distributor::revoke_claim] accounts.
+#[derive(Accounts)]
+pub struct RevokeClaim<'info> {
+    /// Admin key of the distributor.
+    pub admin_auth: Signer<'info>,
+
+    #[account(mut, has_one = admin_auth @ ErrorCode::DistributorAdminMismatch)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Status of the revoked claim, created if the claimant never claimed.
+    #[account(
+    init_if_needed,
+    seeds = [
+    b"ClaimStatus".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    payer = payer,
+    space = 8 + ClaimStatus::LEN,
+    )]
+    pub claim_status: Account<'info, ClaimStatus>,
+
+    /// CHECK: The claimant being revoked, only its key is used.
+    pub claimant: UncheckedAccount<'info>,
 
-    /// Zeta mint, address is checked in the CPI
-    pub zeta_mint: Account<'info, Mint>,
+    /// Distributor ATA containing the tokens to distribute, required if `to` is passed. `native`
+    /// distributors have none.
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
+    pub from: Option<Account<'info, TokenAccount>>,
 
-    /// Who is claiming the tokens.
-    #[account(address = to.owner @ ErrorCode::OwnerMismatch)]
-    pub claimant: Signer<'info>,
+    /// Account to return the revoked amount to, if any.
+    #[account(mut, token::mint = distributor.mint)]
+    pub to: Option<Account<'info, TokenAccount>>,
 
-    /// Payer of the claim.
+    /// Payer of the claim status.
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -574,9 +2053,37 @@ pub struct ClaimStake<'info> {
 
     /// SPL [Token] program.
     pub token_program: Program<'info, Token>,
+
+    /// Status of the leaf once rebound, required if it was, see
+    /// [merkle_distributor::rebind_claim].
+    #[account(
+    mut,
+    seeds = [
+    b"ReboundClaimStatus".as_ref(),
+    distributor.key().to_bytes().as_ref(),
+    claimant.key().to_bytes().as_ref()
+    ],
+    bump,
+    )]
+    pub rebound_claim_status: Option<Account<'info, ClaimStatus>>,
+}
+
+/// Accounts for [merkle_distributor::remove_claim_target].
+#[derive(Accounts)]
+pub struct RemoveClaimTarget<'info> {
+    /// Admin key of the distributor.
+    #[account(mut)]
+    pub admin_auth: Signer<'info>,
+
+    #[account(has_one = admin_auth @ ErrorCode::DistributorAdminMismatch)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// [ClaimTarget] to revoke.
+    #[account(mut, has_one = distributor, close = admin_auth)]
+    pub claim_target: Account<'info, ClaimTarget>,
 }
 
-/// [merkle_distributor::admin_claim_after_expiry] accounts.
+/// [merkle_distributor::admin_claim_after_expiry] and [merkle_distributor::admin_sweep] accounts.
 #[derive(Accounts)]
 pub struct AdminClaimAfterExpiry<'info> {
     /// The [MerkleDistributor].
@@ -584,11 +2091,15 @@ pub struct AdminClaimAfterExpiry<'info> {
     pub distributor: Account<'info, MerkleDistributor>,
 
     /// Distributor ATA containing the tokens to distribute.
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
     pub from: Account<'info, TokenAccount>,
 
     /// Account to send the claimed tokens to.
-    #[account(mut)]
+    #[account(mut, token::mint = distributor.mint)]
     pub to: Account<'info, TokenAccount>,
 
     /// Who is claiming the tokens and the payer.
@@ -602,6 +2113,54 @@ pub struct AdminClaimAfterExpiry<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+/// [merkle_distributor::admin_sweep_native] accounts.
+#[derive(Accounts)]
+pub struct AdminSweepNative<'info> {
+    /// The [MerkleDistributor].
+    #[account(mut, has_one = admin_auth @ ErrorCode::DistributorAdminMismatch)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// The admin, receiving the lamports.
+    #[account(mut)]
+    pub admin_auth: Signer<'info>,
+}
+
+/// [merkle_distributor::set_sweep_recipient] accounts.
+#[derive(Accounts)]
+pub struct SetSweepRecipient<'info> {
+    /// Admin key of the distributor.
+    pub admin_auth: Signer<'info>,
+
+    #[account(mut, has_one = admin_auth @ ErrorCode::DistributorAdminMismatch)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Token account to sweep to, none to unset.
+    #[account(token::mint = distributor.mint)]
+    pub sweep_recipient: Option<Account<'info, TokenAccount>>,
+}
+
+/// [merkle_distributor::sweep_after_expiry] accounts.
+#[derive(Accounts)]
+pub struct SweepAfterExpiry<'info> {
+    /// The [MerkleDistributor].
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.mint,
+        associated_token::authority = distributor,
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// The distributor's sweep recipient.
+    #[account(mut, address = distributor.sweep_recipient @ ErrorCode::SweepRecipientNotSet)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 pub struct UpdateAdminAuth<'info> {
     pub new_admin_auth: Signer<'info>,
@@ -613,61 +2172,340 @@ pub struct UpdateAdminAuth<'info> {
 }
 
 /// State for the account which distributes tokens.
+///
+/// New fields are inserted before `reserved`, taking their space out of it where possible. Accounts
+/// created under an older layout are brought up to date by [merkle_distributor::migrate_distributor].
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct MerkleDistributor {
     /// Base key used to generate the PDA.
-    pub base: Pubkey, // 32
+    pub base: Pubkey,
     /// Admin key used to generate the PDA.
-    pub admin_auth: Pubkey, // 32
+    pub admin_auth: Pubkey,
     /// Bump seed.
-    pub bump: u8, // 1
+    pub bump: u8,
 
     /// The 256-bit merkle root.
-    pub root: [u8; 32], // 32
+    pub root: [u8; 32],
 
     /// [Mint] of the token to be distributed.
-    pub mint: Pubkey, // 32
+    pub mint: Pubkey,
     /// Maximum number of tokens that can ever be claimed from this [MerkleDistributor].
-    pub max_total_claim: u64, // 8
+    pub max_total_claim: u64,
     /// Maximum number of nodes that can ever be claimed from this [MerkleDistributor].
-    pub max_num_nodes: u64, // 8
+    pub max_num_nodes: u64,
     /// Total amount of tokens that have been claimed.
-    pub total_amount_claimed: u64, // 8
+    pub total_amount_claimed: u64,
     /// Number of nodes that have been claimed.
-    pub num_nodes_claimed: u64, // 8
-    /// Timestamp you can start claiming // 8
+    pub num_nodes_claimed: u64,
+    /// Timestamp you can start claiming
     pub claim_start_ts: u64,
-    /// Timestamp you can no longer claim at // 8
+    /// Timestamp you can no longer claim at
     pub claim_end_ts: u64,
-    /// Whether this merkle tree will be directly staked // 1
+    /// Whether this merkle tree will be directly staked
     pub stake_claim_only: bool,
     /// Percentage of allocated tokens you get if you claim immediately, 6dp percentage e.g 60_000000 = 60%
     pub immediate_claim_percentage: u64,
     /// the offset from claim_start_ts in seconds when there is no more discount on claim
     pub later_claim_offset_seconds: u64,
+    /// How the early claim ramp applies to staked claims
+    pub stake_claim_scaling: StakeClaimScaling,
+    /// Base percentage of the ramp for staked claims under [StakeClaimScaling::ScaledWithStakeBase]
+    pub stake_immediate_claim_percentage: u64,
+    /// How leaves and nodes of the tree under `root` are hashed
+    pub hash_scheme: HashScheme,
+    /// Layout of the leaves of the tree under `root`
+    pub leaf_version: LeafVersion,
+    /// Hash function of the tree under `root`
+    pub hash_function: HashFunction,
+
+    /// Layout version of this account, see [MerkleDistributor::VERSION].
+    pub version: u8,
+    /// Token account [merkle_distributor::sweep_after_expiry] sends the vault to, if set.
+    pub sweep_recipient: Pubkey,
+    /// Seconds after `claim_end_ts` during which late claims are still accepted
+    pub grace_period_seconds: u64,
+    /// Percentage of the claim amount late claims get during the grace period, 6dp percentage
+    pub grace_claim_percentage: u64,
+    /// Program flagging claimants that may not claim, see [claim::check_screening]. Unset if the
+    /// default pubkey.
+    pub screening_program: Pubkey,
+    /// Length of the windows claims are rate limited over, zero to disable rate limits
+    pub rate_limit_window_seconds: u64,
+    /// Maximum amount transferred by all claims in a window, zero for no limit
+    pub max_claim_per_window: u64,
+    /// Maximum amount transferred by the claims of a single claimant in a window, zero for no limit
+    pub max_claimant_claim_per_window: u64,
+    /// Amount transferred by all claims in the current window
+    pub rate_limit_window: RateLimitWindow,
+    /// Whether the distributor hands out lamports held by this account instead of tokens from its
+    /// ATA, see [merkle_distributor::claim_native]
+    pub native: bool,
+    /// Space for future fields. Always zero.
+    pub reserved: [u8; 7],
 }
 
 impl MerkleDistributor {
-    pub const LEN: usize = 194;
+    pub const LEN: usize = Self::INIT_SPACE;
+
+    /// Current layout version. Accounts created before versioning was introduced read as 0.
+    pub const VERSION: u8 = 1;
+
+    /// Offset of `admin_auth` in the account data, which is stable across all layout versions.
+    pub const ADMIN_AUTH_OFFSET: usize = 8 + 32;
+
+    /// Amount that can still be claimed, at most.
+    pub fn get_outstanding_amount(&self) -> u64 {
+        self.max_total_claim
+            .saturating_sub(self.total_amount_claimed)
+    }
+
+    /// Last timestamp a claim is accepted at: the end of the claim window plus the grace period.
+    pub fn get_claim_deadline(&self) -> u64 {
+        self.claim_end_ts.saturating_add(self.grace_period_seconds)
+    }
+
+    /// Whether `now` is past the claim window but within its grace period.
+    pub fn is_in_grace_period(&self, now: u64) -> bool {
+        now > self.claim_end_ts && now <= self.get_claim_deadline()
+    }
+
+    /// Amount of a vault holding `vault_amount` the admin may sweep at `now`: all of it once the
+    /// claim window and its grace period are over, before that only the excess above
+    /// [Self::get_outstanding_amount].
+    pub fn get_sweepable_amount(&self, vault_amount: u64, now: u64) -> u64 {
+        if now > self.get_claim_deadline() {
+            vault_amount
+        } else {
+            vault_amount.saturating_sub(self.get_outstanding_amount())
+        }
+    }
+
+    /// Amount actually transferred for a claim of `claim_amount` at `now`, after the early claim ramp.
+    /// Both [merkle_distributor::claim] and [merkle_distributor::claim_stake] go through here.
+    /// Late claims during the grace period get `grace_claim_percentage` instead.
+    pub fn get_transfer_amount(&self, claim_amount: u64, now: u64, stake: bool) -> u64 {
+        if self.is_in_grace_period(now) {
+            return get_percentage(claim_amount, self.grace_claim_percentage);
+        }
+
+        let base_percentage = if stake {
+            match self.stake_claim_scaling {
+                StakeClaimScaling::Full => return claim_amount,
+                StakeClaimScaling::Scaled => self.immediate_claim_percentage,
+                StakeClaimScaling::ScaledWithStakeBase => self.stake_immediate_claim_percentage,
+            }
+        } else {
+            self.immediate_claim_percentage
+        };
+
+        if now < self.claim_start_ts + self.later_claim_offset_seconds {
+            let percent_to_get = get_time_scaled_percentage(
+                base_percentage,
+                now.checked_sub(self.claim_start_ts).unwrap(),
+                self.later_claim_offset_seconds,
+            );
+            get_percentage(claim_amount, percent_to_get)
+        } else {
+            claim_amount
+        }
+    }
 }
 
-/// Holds whether or not a claimant has claimed tokens.
-///
-/// TODO: this is probably better stored as the node that was verified.
+/// How the early claim ramp applies to [merkle_distributor::claim_stake].
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum StakeClaimScaling {
+    /// Staked claims always receive the full claim amount.
+    #[default]
+    Full,
+    /// Staked claims follow the same ramp as [merkle_distributor::claim].
+    Scaled,
+    /// Staked claims follow the ramp starting from `stake_immediate_claim_percentage`
+    /// instead of `immediate_claim_percentage`.
+    ScaledWithStakeBase,
+}
+
+/// A limit on the amount claims transfer per window, see [MerkleDistributor::rate_limit_window].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimit {
+    /// [MerkleDistributor::max_claim_per_window].
+    Global,
+    /// [MerkleDistributor::max_claimant_claim_per_window].
+    Claimant,
+}
+
+/// Amount transferred by claims in a rate limit window.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct RateLimitWindow {
+    /// Start of the window, a multiple of the window length.
+    pub start_ts: u64,
+    /// Amount transferred in the window.
+    pub claimed_amount: u64,
+}
+
+impl RateLimitWindow {
+    /// Amount that may still be transferred at `now` without exceeding `max`, `None` if `max` is
+    /// zero, meaning no limit.
+    pub fn remaining(&self, now: u64, window_seconds: u64, max: u64) -> Option<u64> {
+        if max == 0 {
+            return None;
+        }
+        if now - now % window_seconds != self.start_ts {
+            return Some(max);
+        }
+        Some(max.saturating_sub(self.claimed_amount))
+    }
+
+    /// Adds `amount` transferred at `now`, moving to the window of `window_seconds` that contains
+    /// `now` first. Returns false if that would exceed `max`, zero meaning no limit.
+    pub fn try_add(&mut self, amount: u64, now: u64, window_seconds: u64, max: u64) -> bool {
+        let start_ts = now - now % window_seconds;
+        if start_ts != self.start_ts {
+            *self = Self {
+                start_ts,
+                claimed_amount: 0,
+            };
+        }
+        let claimed_amount = self.claimed_amount.saturating_add(amount);
+        if max != 0 && claimed_amount > max {
+            return false;
+        }
+        self.claimed_amount = claimed_amount;
+        true
+    }
+}
+
+/// A program instruction that [merkle_distributor::claim_into_program] may forward claims into.
 #[account]
 #[derive(Default)]
+pub struct ClaimTarget {
+    /// The [MerkleDistributor] this target is approved for.
+    pub distributor: Pubkey, // 32
+    /// Program the claimed tokens are forwarded into.
+    pub program_id: Pubkey, // 32
+    /// Prefix the forwarded instruction data must start with.
+    pub ix_discriminator: [u8; 8], // 8
+    /// Number of bytes of `ix_discriminator` that are checked.
+    pub discriminator_len: u8, // 1
+    /// Offset in the forwarded instruction data of the little-endian u64 amount.
+    pub amount_offset: u16, // 2
+    /// Bump seed.
+    pub bump: u8, // 1
+}
+
+impl ClaimTarget {
+    pub const LEN: usize = 76;
+
+    pub fn discriminator(&self) -> &[u8] {
+        &self.ix_discriminator[..self.discriminator_len as usize]
+    }
+}
+
+/// A wallet a claimant allows to claim on its behalf, see [merkle_distributor::claim_as_delegate].
+#[account]
+#[derive(Default, InitSpace)]
+pub struct ClaimDelegate {
+    /// The claimant that registered the delegate.
+    pub claimant: Pubkey,
+    /// Wallet allowed to claim for the claimant.
+    pub delegate: Pubkey,
+    /// Owner of the token accounts delegated claims are sent to.
+    pub destination: Pubkey,
+    /// Bump seed.
+    pub bump: u8,
+}
+
+impl ClaimDelegate {
+    pub const LEN: usize = Self::INIT_SPACE;
+}
+
+/// Records that a leaf was rebound to `new_claimant`, see [merkle_distributor::rebind_claim].
+#[account]
+#[derive(Default, InitSpace)]
+pub struct ClaimRebind {
+    /// The [MerkleDistributor].
+    pub distributor: Pubkey,
+    /// Claimant of the rebound leaf.
+    pub claimant: Pubkey,
+    /// Wallet that now claims the leaf.
+    pub new_claimant: Pubkey,
+    /// Bump seed.
+    pub bump: u8,
+}
+
+impl ClaimRebind {
+    pub const LEN: usize = Self::INIT_SPACE;
+}
+
+/// Holds whether or not a claimant has claimed tokens, and which leaf they claimed.
+///
+/// New fields take their space out of `reserved`. Accounts created under an older, shorter layout
+/// are brought up to date by [merkle_distributor::migrate_claim_status].
+#[account]
+#[derive(Default, InitSpace)]
 pub struct ClaimStatus {
     /// Authority that claimed the tokens.
-    pub claimant: Pubkey, // 64
-    /// When the tokens were claimed.
-    pub claimed_at: i64, // 8
+    pub claimant: Pubkey,
+    /// When the tokens were last claimed.
+    pub claimed_at: i64,
     /// Amount of tokens claimed.
-    pub claimed_amount: u64, // 8
+    pub claimed_amount: u64,
+    /// Index of the claimed leaf.
+    pub index: u64,
+    /// Root the leaf was last claimed against.
+    pub root: [u8; 32],
+    /// Number of claims made, more than one for vesting leaves.
+    pub num_claims: u64,
+    /// Who paid the rent of this account.
+    pub payer: Pubkey,
+    /// When the admin revoked the leaf, zero if it was not.
+    pub revoked_at: i64,
+    /// Amount transferred by the claimant's claims in the current window.
+    pub rate_limit_window: RateLimitWindow,
+    /// Wallet the leaf was rebound to, the default pubkey if it was not.
+    pub rebound_to: Pubkey,
+    /// Space for future fields. Always zero.
+    pub reserved: [u64; 4],
 }
 
 impl ClaimStatus {
-    pub const LEN: usize = 80;
+    pub const LEN: usize = Self::INIT_SPACE;
+}
+
+/// Number of remaining accounts per [ClaimManyEntry].
+pub const CLAIM_MANY_ACCOUNTS: usize = 4;
+
+/// A single claim of [merkle_distributor::claim_many].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct ClaimManyEntry {
+    /// Index of the leaf.
+    pub index: u64,
+    /// Amount of the leaf.
+    pub amount: u64,
+    /// Terms of the leaf, see [merkle_distributor::claim].
+    pub terms: Option<LeafTerms>,
+    /// Number of hashes of the instruction's `proofs` that belong to this entry.
+    pub proof_len: u8,
+}
+
+/// Number of remaining accounts per [DistributeBatchEntry].
+pub const DISTRIBUTE_BATCH_ACCOUNTS: usize = 2;
+
+/// A single leaf pushed by [merkle_distributor::distribute_batch].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct DistributeBatchEntry {
+    /// Index of the leaf.
+    pub index: u64,
+    /// Claimant of the leaf.
+    pub claimant: Pubkey,
+    /// Amount of the leaf.
+    pub amount: u64,
+    /// Terms of the leaf, see [merkle_distributor::claim].
+    pub terms: Option<LeafTerms>,
 }
 
 /// Emitted when tokens are claimed.
@@ -680,6 +2518,246 @@ pub struct ClaimedEvent {
     pub claimant: Pubkey,
     /// Amount of tokens to distribute.
     pub claim_amount: u64,
+    /// The [MerkleDistributor] claimed from.
+    pub distributor: Pubkey,
+    /// Amount of tokens that left the vault, after the early claim ramp.
+    pub transfer_amount: u64,
+    /// Part of `claim_amount` given up to the early claim ramp.
+    pub forfeited_amount: u64,
+    /// Token account, or stake account if staked, the tokens went to.
+    pub destination: Pubkey,
+    /// Whether the tokens were claimed via [merkle_distributor::claim_stake].
+    pub staked: bool,
+    /// Duration the tokens were staked for, 0 if not staked.
+    pub stake_duration_epochs: u32,
+}
+
+/// Emitted by [merkle_distributor::new_distributor].
+#[event]
+pub struct DistributorCreatedEvent {
+    pub distributor: Pubkey,
+    pub base: Pubkey,
+    pub admin_auth: Pubkey,
+    pub mint: Pubkey,
+    pub root: [u8; 32],
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+    pub claim_start_ts: u64,
+    pub claim_end_ts: u64,
+    pub stake_claim_only: bool,
+    pub immediate_claim_percentage: u64,
+    pub later_claim_offset_seconds: u64,
+    pub stake_claim_scaling: StakeClaimScaling,
+    pub stake_immediate_claim_percentage: u64,
+    pub hash_scheme: HashScheme,
+    pub leaf_version: LeafVersion,
+    pub hash_function: HashFunction,
+    pub native: bool,
+}
+
+/// Emitted by [merkle_distributor::migrate_claim_status].
+#[event]
+pub struct ClaimStatusMigratedEvent {
+    pub claim_status: Pubkey,
+    pub claimant: Pubkey,
+}
+
+/// Emitted by [merkle_distributor::migrate_distributor].
+#[event]
+pub struct DistributorMigratedEvent {
+    pub distributor: Pubkey,
+    /// Layout version the distributor was migrated to.
+    pub version: u8,
+}
+
+/// Emitted by [merkle_distributor::update_distributor].
+#[event]
+pub struct RootUpdatedEvent {
+    pub distributor: Pubkey,
+    pub root: [u8; 32],
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+}
+
+/// Emitted by [merkle_distributor::update_distributor_claim_window].
+#[event]
+pub struct ClaimWindowUpdatedEvent {
+    pub distributor: Pubkey,
+    pub claim_start_ts: u64,
+    pub claim_end_ts: u64,
+}
+
+/// Emitted by [merkle_distributor::update_distributor_claim_percentages].
+#[event]
+pub struct ClaimPercentagesUpdatedEvent {
+    pub distributor: Pubkey,
+    pub immediate_claim_percentage: u64,
+    pub later_claim_offset_seconds: u64,
+}
+
+/// Emitted by [merkle_distributor::update_distributor_grace_period].
+#[event]
+pub struct GracePeriodUpdatedEvent {
+    pub distributor: Pubkey,
+    pub grace_period_seconds: u64,
+    pub grace_claim_percentage: u64,
+}
+
+/// Emitted by [merkle_distributor::update_distributor_rate_limits].
+#[event]
+pub struct RateLimitsUpdatedEvent {
+    pub distributor: Pubkey,
+    pub rate_limit_window_seconds: u64,
+    pub max_claim_per_window: u64,
+    pub max_claimant_claim_per_window: u64,
+}
+
+/// Emitted instead of a [ClaimedEvent] when [merkle_distributor::claim_many] or
+/// [merkle_distributor::distribute_batch] skip a claim for exceeding a rate limit, see
+/// [merkle_distributor::update_distributor_rate_limits].
+#[event]
+pub struct RateLimitTrippedEvent {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    /// Amount the skipped claim would have transferred.
+    pub transfer_amount: u64,
+    /// The limit it would have exceeded.
+    pub limit: RateLimit,
+}
+
+/// Emitted by [merkle_distributor::update_distributor_screening_program].
+#[event]
+pub struct ScreeningProgramUpdatedEvent {
+    pub distributor: Pubkey,
+    /// The new screening program, the default pubkey if screening is disabled.
+    pub screening_program: Pubkey,
+}
+
+/// Emitted by [merkle_distributor::set_claim_delegate].
+#[event]
+pub struct ClaimDelegateSetEvent {
+    pub claimant: Pubkey,
+    pub delegate: Pubkey,
+    pub destination: Pubkey,
+}
+
+/// Emitted by [merkle_distributor::remove_claim_delegate].
+#[event]
+pub struct ClaimDelegateRemovedEvent {
+    pub claimant: Pubkey,
+    pub delegate: Pubkey,
+}
+
+/// Emitted by [merkle_distributor::rebind_claim].
+#[event]
+pub struct ClaimReboundEvent {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub new_claimant: Pubkey,
+    pub index: u64,
+}
+
+/// Emitted by [merkle_distributor::revoke_claim].
+#[event]
+pub struct ClaimRevokedEvent {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    /// Unclaimed amount of the leaf, taken off `max_total_claim`.
+    pub revoked_amount: u64,
+    /// Whether the revoked amount was returned out of the vault.
+    pub returned: bool,
+}
+
+/// Emitted by [merkle_distributor::update_distributor_stake_claim_scaling].
+#[event]
+pub struct StakeClaimScalingUpdatedEvent {
+    pub distributor: Pubkey,
+    pub stake_claim_scaling: StakeClaimScaling,
+    pub stake_immediate_claim_percentage: u64,
+}
+
+/// Emitted by [merkle_distributor::update_distributor_admin_auth] and
+/// [merkle_distributor::update_admin_auth].
+#[event]
+pub struct AdminAuthUpdatedEvent {
+    pub distributor: Pubkey,
+    pub old_admin_auth: Pubkey,
+    pub new_admin_auth: Pubkey,
+}
+
+/// Emitted by [merkle_distributor::add_claim_target].
+#[event]
+pub struct ClaimTargetAddedEvent {
+    pub distributor: Pubkey,
+    pub program_id: Pubkey,
+    pub ix_discriminator: [u8; 8],
+    pub discriminator_len: u8,
+    pub amount_offset: u16,
+}
+
+/// Emitted by [merkle_distributor::remove_claim_target].
+#[event]
+pub struct ClaimTargetRemovedEvent {
+    pub distributor: Pubkey,
+    pub program_id: Pubkey,
+}
+
+/// Emitted by [merkle_distributor::set_sweep_recipient].
+#[event]
+pub struct SweepRecipientUpdatedEvent {
+    pub distributor: Pubkey,
+    /// The new sweep recipient, the default pubkey if unset.
+    pub sweep_recipient: Pubkey,
+}
+
+/// Emitted by [merkle_distributor::admin_sweep] and [merkle_distributor::sweep_after_expiry].
+#[event]
+pub struct SweptEvent {
+    pub distributor: Pubkey,
+    /// Token account the tokens went to.
+    pub to: Pubkey,
+    /// Amount of tokens swept out of the vault.
+    pub amount: u64,
+    /// Whether the sweep was triggered through [merkle_distributor::sweep_after_expiry].
+    pub permissionless: bool,
+}
+
+/// Emitted by [merkle_distributor::admin_claim_after_expiry].
+#[event]
+pub struct AdminClaimedAfterExpiryEvent {
+    pub distributor: Pubkey,
+    /// Token account the remaining tokens went to.
+    pub to: Pubkey,
+    /// Amount of tokens swept out of the vault.
+    pub amount: u64,
+}
+
+/// Reallocates a program owned account to `space`, topping up its rent from `payer`.
+fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    space: usize,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(info.lamports());
+    if lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: info.clone(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+    info.realloc(space, true)?;
+    Ok(())
 }
 
 // Percentage is 6dp e.g 60% = 60_000000;
@@ -708,7 +2786,7 @@ pub fn get_time_scaled_percentage(
         .unwrap();
 
     let scaled_percentage = (base_percentage as u128)
-        .checked_add((a as u128).checked_div(total_offset_time as u128).unwrap())
+        .checked_add(a.checked_div(total_offset_time as u128).unwrap())
         .unwrap();
 
     scaled_percentage.try_into().unwrap()
@@ -745,4 +2823,156 @@ pub enum ErrorCode {
     InsideClaimWindow,
     #[msg("Invalid stake duration")]
     InvalidStakeDuration,
+    #[msg("Invalid claim target")]
+    InvalidClaimTarget,
+    #[msg("Instruction data does not match the claim target")]
+    InvalidClaimTargetInstruction,
+    #[msg("Claim target did not consume the claimed tokens")]
+    ClaimTargetDidNotConsume,
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+    #[msg("Invalid claim status account")]
+    InvalidClaimStatus,
+    #[msg("Leaf terms do not match the distributor leaf version")]
+    InvalidLeafTerms,
+    #[msg("Invalid distributor account")]
+    InvalidDistributorAccount,
+    #[msg("Distributor is already on the current layout version")]
+    DistributorAlreadyMigrated,
+    #[msg("Token account mint did not match the distributor mint")]
+    MintMismatch,
+    #[msg("Amount exceeds what can be swept")]
+    ExceededSweepableAmount,
+    #[msg("No sweep recipient is set")]
+    SweepRecipientNotSet,
+    #[msg("Claim was revoked by the admin")]
+    ClaimRevoked,
+    #[msg("Claimant is flagged by the distributor's screening program")]
+    ClaimantFlagged,
+    #[msg("Screening flag account is missing or not the claimant's")]
+    InvalidScreeningFlag,
+    #[msg("Claims from a screened distributor must go through claim, claim_stake, claim_into_program, claim_as_delegate or claim_native")]
+    ScreenedDistributor,
+    #[msg("Native distributors only pay out through claim_native and admin_sweep_native")]
+    NativeDistributor,
+    #[msg("Distributor does not distribute native lamports")]
+    NotNativeDistributor,
+    #[msg("Distributor does not hold enough lamports")]
+    InsufficientNativeBalance,
+    #[msg("Claim was rebound to another wallet")]
+    ClaimRebound,
+    #[msg("Claim status is already on the current layout")]
+    ClaimStatusAlreadyMigrated,
+    #[msg("Claim exceeds the rate limits of the distributor")]
+    RateLimited,
+    #[msg("Native distributors are swept with admin_sweep_native")]
+    NativeSweepRecipient,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Size of the [MerkleDistributor] fields before versioning, after the discriminator.
+    const UNVERSIONED_LEN: usize = 194;
+
+    #[test]
+    fn test_migrated_layout() {
+        let distributor = MerkleDistributor {
+            base: Pubkey::new_unique(),
+            admin_auth: Pubkey::new_unique(),
+            root: [7; 32],
+            max_total_claim: 1_000,
+            claim_end_ts: 100,
+            immediate_claim_percentage: PERCENT_100,
+            later_claim_offset_seconds: 10,
+            ..MerkleDistributor::default()
+        };
+        let mut data = Vec::new();
+        distributor.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 8 + MerkleDistributor::LEN);
+
+        // An account created before versioning, grown by migrate_distributor.
+        data.truncate(8 + UNVERSIONED_LEN);
+        assert!(MerkleDistributor::try_deserialize(&mut &data[..]).is_err());
+        data.resize(8 + MerkleDistributor::LEN, 0);
+
+        let migrated = MerkleDistributor::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(migrated.version, 0);
+        assert_eq!(migrated.admin_auth, distributor.admin_auth);
+        assert_eq!(
+            data[MerkleDistributor::ADMIN_AUTH_OFFSET..][..32],
+            distributor.admin_auth.to_bytes()
+        );
+        assert_eq!(migrated.root, distributor.root);
+        assert_eq!(migrated.later_claim_offset_seconds, 10);
+        assert_eq!(migrated.stake_claim_scaling, StakeClaimScaling::Full);
+        assert_eq!(migrated.hash_scheme, HashScheme::Legacy);
+        assert_eq!(migrated.leaf_version, LeafVersion::V0);
+        assert_eq!(migrated.hash_function, HashFunction::Keccak);
+    }
+
+    #[test]
+    fn test_migrated_claim_status_layout() {
+        // The original layout: claimant, claimed_at and claimed_amount in an 80 byte account.
+        let claimant = Pubkey::new_unique();
+        let mut data = ClaimStatus::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&claimant.to_bytes());
+        data.extend_from_slice(&42i64.to_le_bytes());
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.resize(8 + 80, 0);
+        assert!(ClaimStatus::try_deserialize(&mut &data[..]).is_err());
+
+        // Grown by migrate_claim_status.
+        data.resize(8 + ClaimStatus::LEN, 0);
+        let migrated = ClaimStatus::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(migrated.claimant, claimant);
+        assert_eq!(migrated.claimed_at, 42);
+        assert_eq!(migrated.claimed_amount, 500);
+        assert_eq!(migrated.revoked_at, 0);
+        assert_eq!(migrated.rebound_to, Pubkey::default());
+    }
+
+    #[test]
+    fn test_sweepable_amount() {
+        let distributor = MerkleDistributor {
+            max_total_claim: 1_000,
+            total_amount_claimed: 400,
+            claim_end_ts: 100,
+            ..MerkleDistributor::default()
+        };
+        assert_eq!(distributor.get_outstanding_amount(), 600);
+
+        // Only the excess inside the window, e.g. forfeited to the early claim ramp.
+        assert_eq!(distributor.get_sweepable_amount(700, 100), 100);
+        assert_eq!(distributor.get_sweepable_amount(500, 50), 0);
+        // Everything after it.
+        assert_eq!(distributor.get_sweepable_amount(700, 101), 700);
+    }
+
+    #[test]
+    fn test_grace_period() {
+        let distributor = MerkleDistributor {
+            max_total_claim: 1_000,
+            claim_end_ts: 100,
+            immediate_claim_percentage: PERCENT_100,
+            grace_period_seconds: 50,
+            grace_claim_percentage: 40_000000,
+            ..MerkleDistributor::default()
+        };
+        assert_eq!(distributor.get_claim_deadline(), 150);
+        assert!(!distributor.is_in_grace_period(100));
+        assert!(distributor.is_in_grace_period(101));
+        assert!(distributor.is_in_grace_period(150));
+        assert!(!distributor.is_in_grace_period(151));
+
+        // Late claims are cut to the grace percentage, staked ones included.
+        assert_eq!(distributor.get_transfer_amount(100, 100, false), 100);
+        assert_eq!(distributor.get_transfer_amount(100, 120, false), 40);
+        assert_eq!(distributor.get_transfer_amount(100, 120, true), 40);
+
+        // Sweeps wait until the grace period is over.
+        assert_eq!(distributor.get_sweepable_amount(1_000, 150), 0);
+        assert_eq!(distributor.get_sweepable_amount(1_000, 151), 1_000);
+    }
 }
@@ -1,3 +1,10 @@
+use std::collections::VecDeque;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash, keccak, poseidon};
+
+use crate::ErrorCode;
+
 /// modified version of https://github.com/saber-hq/merkle-distributor/blob/ac937d1901033ecb7fa3b0db22f7b39569c8e052/programs/merkle-distributor/src/merkle_proof.rs#L8
 /// This function deals with verification of Merkle trees (hash trees).
 /// Direct port of https://github.com/OpenZeppelin/openzeppelin-contracts/blob/v3.4.0/contracts/cryptography/MerkleProof.sol
@@ -5,20 +12,190 @@
 /// defined by `root`. For this, a `proof` must be provided, containing
 /// sibling hashes on the branch from the leaf to the root of the tree. Each
 /// pair of leaves and each pair of pre-images are assumed to be sorted.
-
-pub fn verify(proof: Vec<[u8; 32]>, root: [u8; 32], leaf: [u8; 32]) -> bool {
+/// Internal nodes are hashed with `hasher` according to `scheme`.
+pub fn verify<H: Hasher>(
+    proof: Vec<[u8; 32]>,
+    root: [u8; 32],
+    leaf: [u8; 32],
+    scheme: HashScheme,
+    hasher: &H,
+) -> Result<bool> {
     let mut computed_hash = leaf;
     for proof_element in proof.into_iter() {
-        if computed_hash <= proof_element {
-            // Hash(current computed hash + current element of the proof)
-            computed_hash =
-                anchor_lang::solana_program::keccak::hashv(&[&computed_hash, &proof_element]).0;
-        } else {
-            // Hash(current element of the proof + current computed hash)
-            computed_hash =
-                anchor_lang::solana_program::keccak::hashv(&[&proof_element, &computed_hash]).0;
-        }
+        computed_hash = scheme.hash_pair(hasher, computed_hash, proof_element)?;
     }
     // Check if the computed hash (root) is equal to the provided root
-    computed_hash == root
+    Ok(computed_hash == root)
+}
+
+/// A 256-bit hash function.
+pub trait Hasher {
+    /// Hash of the concatenation of `vals`.
+    fn hashv(&self, vals: &[&[u8]]) -> Result<[u8; 32]>;
+}
+
+/// Keccak-256, through the `sol_keccak256` syscall.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Keccak;
+
+impl Hasher for Keccak {
+    fn hashv(&self, vals: &[&[u8]]) -> Result<[u8; 32]> {
+        Ok(keccak::hashv(vals).0)
+    }
+}
+
+/// SHA-256, through the `sol_sha256` syscall.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256;
+
+impl Hasher for Sha256 {
+    fn hashv(&self, vals: &[&[u8]]) -> Result<[u8; 32]> {
+        Ok(hash::hashv(vals).to_bytes())
+    }
+}
+
+/// Poseidon over BN254 (x^5, circom compatible), through the `sol_poseidon` syscall.
+///
+/// The concatenated input is split into big-endian chunks of [Poseidon::CHUNK_LEN] bytes, each of
+/// which is below the field modulus, so at most `12 * CHUNK_LEN` bytes can be hashed. Inputs of
+/// different lengths may collide, which is fine for the fixed-size leaves and nodes hashed here.
+/// Inputs the syscall rejects fail with [ErrorCode::InvalidProof].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Poseidon;
+
+impl Poseidon {
+    pub const CHUNK_LEN: usize = 31;
+}
+
+impl Hasher for Poseidon {
+    fn hashv(&self, vals: &[&[u8]]) -> Result<[u8; 32]> {
+        let data = vals.concat();
+        let chunks: Vec<&[u8]> = data.chunks(Self::CHUNK_LEN).collect();
+        let hash = poseidon::hashv(
+            poseidon::Parameters::Bn254X5,
+            poseidon::Endianness::BigEndian,
+            &chunks,
+        )
+        .map_err(|_| ErrorCode::InvalidProof)?;
+        Ok(hash.to_bytes())
+    }
+}
+
+/// The [Hasher] of a distributor's tree, chosen at creation.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum HashFunction {
+    #[default]
+    Keccak,
+    Sha256,
+    Poseidon,
+}
+
+impl Hasher for HashFunction {
+    fn hashv(&self, vals: &[&[u8]]) -> Result<[u8; 32]> {
+        match self {
+            HashFunction::Keccak => Keccak.hashv(vals),
+            HashFunction::Sha256 => Sha256.hashv(vals),
+            HashFunction::Poseidon => Poseidon.hashv(vals),
+        }
+    }
+}
+
+/// Domain tag prefixed to leaves by the tagged [HashScheme]s.
+pub const LEAF_TAG: u8 = 0;
+/// Domain tag prefixed to internal nodes by the tagged [HashScheme]s.
+pub const NODE_TAG: u8 = 1;
+
+/// How the leaves and internal nodes of a tree are hashed.
+///
+/// The tagged schemes prefix distinct domain tags to leaves and nodes, so a 64-byte internal node
+/// can never be passed off as a leaf (second preimage).
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum HashScheme {
+    /// `H(leaf)` and `H(a || b)`.
+    #[default]
+    Legacy,
+    /// `H(LEAF_TAG || leaf)` and `H(NODE_TAG || a || b)`.
+    Tagged,
+    /// `H(LEAF_TAG || H(leaf))` and `H(NODE_TAG || a || b)`.
+    TaggedDoubleHashed,
+}
+
+impl HashScheme {
+    /// Hashes the concatenation of `data` into a leaf.
+    pub fn hash_leaf<H: Hasher>(&self, hasher: &H, data: &[&[u8]]) -> Result<[u8; 32]> {
+        match self {
+            HashScheme::Legacy => hasher.hashv(data),
+            HashScheme::Tagged => hasher.hashv(&[&[&[LEAF_TAG][..]], data].concat()),
+            HashScheme::TaggedDoubleHashed => hasher.hashv(&[&[LEAF_TAG], &hasher.hashv(data)?]),
+        }
+    }
+
+    /// Hash of a sorted pair of nodes.
+    pub fn hash_pair<H: Hasher>(&self, hasher: &H, a: [u8; 32], b: [u8; 32]) -> Result<[u8; 32]> {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        match self {
+            HashScheme::Legacy => hasher.hashv(&[&first, &second]),
+            HashScheme::Tagged | HashScheme::TaggedDoubleHashed => {
+                hasher.hashv(&[&[NODE_TAG], &first, &second])
+            }
+        }
+    }
+}
+
+/// How [verify_multi] obtains the sibling of the next node it hashes.
+///
+/// Mirrors the `proofFlags` of OpenZeppelin's `multiProofVerify`, with an extra [ProofFlag::Promote]
+/// for the last node of an odd-sized layer, which our trees carry up unhashed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofFlag {
+    /// The sibling is the next element of the proof.
+    Proof,
+    /// The sibling is the next leaf or previously computed hash.
+    Queue,
+    /// The node has no sibling and moves up a layer as-is.
+    Promote,
+}
+
+/// Returns true if all `leaves` can be proved to be a part of the Merkle tree defined by `root`.
+///
+/// Leaves and computed hashes are consumed as a queue, so `leaves` must be in the order they appear
+/// in the tree. Each flag consumes one node of the queue and pushes its parent; `proof` holds the
+/// siblings that cannot be computed from the leaves. See [crate::merkle_tree::MerkleTree::multi_proof].
+pub fn verify_multi<H: Hasher>(
+    leaves: &[[u8; 32]],
+    proof: &[[u8; 32]],
+    proof_flags: &[ProofFlag],
+    root: [u8; 32],
+    scheme: HashScheme,
+    hasher: &H,
+) -> Result<bool> {
+    if leaves.is_empty() {
+        return Ok(false);
+    }
+
+    let mut queue: VecDeque<[u8; 32]> = leaves.iter().copied().collect();
+    let mut proof = proof.iter();
+    for flag in proof_flags {
+        let Some(node) = queue.pop_front() else {
+            return Ok(false);
+        };
+        let parent = match flag {
+            ProofFlag::Proof => match proof.next() {
+                Some(sibling) => scheme.hash_pair(hasher, node, *sibling)?,
+                None => return Ok(false),
+            },
+            ProofFlag::Queue => match queue.pop_front() {
+                Some(sibling) => scheme.hash_pair(hasher, node, sibling)?,
+                None => return Ok(false),
+            },
+            ProofFlag::Promote => node,
+        };
+        queue.push_back(parent);
+    }
+
+    Ok(proof.next().is_none() && queue.len() == 1 && queue[0] == root)
 }
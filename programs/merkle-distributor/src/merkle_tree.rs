@@ -0,0 +1,306 @@
+//! Off-chain Merkle tree builder, matching the layout of the SDK's `MerkleTree`.
+//!
+//! Leaves are sorted and deduplicated, each layer hashes sorted pairs of nodes, and the last node
+//! of an odd-sized layer is carried up unhashed.
+
+use std::collections::BTreeSet;
+
+use crate::merkle_proof::{HashFunction, HashScheme, Hasher, ProofFlag};
+
+/// A proof that several leaves are part of a tree, verified by [crate::merkle_proof::verify_multi].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MultiProof {
+    /// The proven leaves, in tree order.
+    pub leaves: Vec<[u8; 32]>,
+    /// Sibling hashes that cannot be computed from the leaves.
+    pub proof: Vec<[u8; 32]>,
+    /// One flag per hash step.
+    pub proof_flags: Vec<ProofFlag>,
+}
+
+/// A Merkle tree over 32-byte leaves.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<H: Hasher = HashFunction> {
+    layers: Vec<Vec<[u8; 32]>>,
+    scheme: HashScheme,
+    hasher: H,
+}
+
+impl<H: Hasher> MerkleTree<H> {
+    /// Builds a tree from already hashed `leaves`, hashing nodes with `hasher` according to
+    /// `scheme`. Returns `None` if there are no leaves or a node cannot be hashed.
+    pub fn new(mut leaves: Vec<[u8; 32]>, scheme: HashScheme, hasher: H) -> Option<Self> {
+        if leaves.is_empty() {
+            return None;
+        }
+        leaves.sort_unstable();
+        leaves.dedup();
+
+        let mut layers = vec![leaves];
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            let next = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => scheme.hash_pair(&hasher, *a, *b).ok(),
+                    [a] => Some(*a),
+                    _ => unreachable!(),
+                })
+                .collect::<Option<_>>()?;
+            layers.push(next);
+        }
+
+        Some(Self {
+            layers,
+            scheme,
+            hasher,
+        })
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// How the nodes of the tree are hashed.
+    pub fn scheme(&self) -> HashScheme {
+        self.scheme
+    }
+
+    /// The hash function of the tree.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// The leaves of the tree, in tree order.
+    pub fn leaves(&self) -> &[[u8; 32]] {
+        &self.layers[0]
+    }
+
+    /// Proof of a single leaf, for [crate::merkle_proof::verify].
+    pub fn proof(&self, leaf: &[u8; 32]) -> Option<Vec<[u8; 32]>> {
+        let mut idx = self.position(leaf)?;
+        let mut proof = vec![];
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(idx ^ 1) {
+                proof.push(*sibling);
+            }
+            idx /= 2;
+        }
+        Some(proof)
+    }
+
+    /// Multiproof of `leaves`, for [crate::merkle_proof::verify_multi].
+    /// Returns `None` if any leaf is not part of the tree.
+    pub fn multi_proof(&self, leaves: &[[u8; 32]]) -> Option<MultiProof> {
+        let mut known = leaves
+            .iter()
+            .map(|leaf| self.position(leaf))
+            .collect::<Option<BTreeSet<usize>>>()?;
+
+        let mut multi_proof = MultiProof {
+            leaves: known.iter().map(|&idx| self.layers[0][idx]).collect(),
+            ..MultiProof::default()
+        };
+
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let mut parents = BTreeSet::new();
+            let mut nodes = known.iter().peekable();
+            while let Some(&idx) = nodes.next() {
+                let sibling = idx ^ 1;
+                let flag = if sibling >= layer.len() {
+                    ProofFlag::Promote
+                } else if nodes.peek() == Some(&&sibling) {
+                    nodes.next();
+                    ProofFlag::Queue
+                } else {
+                    multi_proof.proof.push(layer[sibling]);
+                    ProofFlag::Proof
+                };
+                multi_proof.proof_flags.push(flag);
+                parents.insert(idx / 2);
+            }
+            known = parents;
+        }
+
+        Some(multi_proof)
+    }
+
+    fn position(&self, leaf: &[u8; 32]) -> Option<usize> {
+        self.layers[0].binary_search(leaf).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle_proof::{verify, verify_multi, Keccak, Poseidon};
+
+    const SCHEMES: [HashScheme; 3] = [
+        HashScheme::Legacy,
+        HashScheme::Tagged,
+        HashScheme::TaggedDoubleHashed,
+    ];
+
+    const HASH_FUNCTIONS: [HashFunction; 3] = [
+        HashFunction::Keccak,
+        HashFunction::Sha256,
+        HashFunction::Poseidon,
+    ];
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n)
+            .map(|i| anchor_lang::solana_program::keccak::hash(&[i]).0)
+            .collect()
+    }
+
+    #[test]
+    fn single_proofs_verify() {
+        for hasher in HASH_FUNCTIONS {
+            for scheme in SCHEMES {
+                for n in 1..=9 {
+                    let leaves = leaves(n);
+                    let tree = MerkleTree::new(leaves.clone(), scheme, hasher).unwrap();
+                    for leaf in &leaves {
+                        let proof = tree.proof(leaf).unwrap();
+                        assert!(verify(proof, tree.root(), *leaf, scheme, &hasher).unwrap());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hash_functions_are_not_interchangeable() {
+        let leaves = leaves(4);
+        let roots: Vec<[u8; 32]> = HASH_FUNCTIONS
+            .iter()
+            .map(|hasher| {
+                MerkleTree::new(leaves.clone(), HashScheme::Tagged, *hasher)
+                    .unwrap()
+                    .root()
+            })
+            .collect();
+        assert_ne!(roots[0], roots[1]);
+        assert_ne!(roots[0], roots[2]);
+        assert_ne!(roots[1], roots[2]);
+
+        let tree =
+            MerkleTree::new(leaves.clone(), HashScheme::Tagged, HashFunction::Poseidon).unwrap();
+        let proof = tree.proof(&leaves[0]).unwrap();
+        assert!(!verify(proof, tree.root(), leaves[0], HashScheme::Tagged, &Keccak).unwrap());
+    }
+
+    #[test]
+    fn poseidon_rejects_oversized_inputs() {
+        let data = [1u8; 13 * Poseidon::CHUNK_LEN];
+        assert!(Poseidon.hashv(&[&data]).is_err());
+        assert!(HashScheme::Tagged
+            .hash_leaf(&HashFunction::Poseidon, &[&data])
+            .is_err());
+    }
+
+    #[test]
+    fn schemes_are_not_interchangeable() {
+        let leaves = leaves(4);
+        let legacy = MerkleTree::new(leaves.clone(), HashScheme::Legacy, Keccak).unwrap();
+        let tagged = MerkleTree::new(leaves.clone(), HashScheme::Tagged, Keccak).unwrap();
+        assert_ne!(legacy.root(), tagged.root());
+
+        let proof = tagged.proof(&leaves[0]).unwrap();
+        assert!(!verify(proof, tagged.root(), leaves[0], HashScheme::Legacy, &Keccak).unwrap());
+    }
+
+    #[test]
+    fn tagged_nodes_cannot_pass_as_leaves() {
+        // Under the legacy scheme an internal node is the hash of 64 bytes, so its two children
+        // can be presented as a "leaf" with a shorter proof.
+        let data: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; 48]).collect();
+        for scheme in SCHEMES {
+            let hashed: Vec<[u8; 32]> = data
+                .iter()
+                .map(|d| scheme.hash_leaf(&Keccak, &[d]).unwrap())
+                .collect();
+            let tree = MerkleTree::new(hashed.clone(), scheme, Keccak).unwrap();
+            let mut sorted = hashed.clone();
+            sorted.sort_unstable();
+            let preimage = [sorted[0], sorted[1]].concat();
+            let forged = scheme.hash_leaf(&Keccak, &[&preimage]).unwrap();
+            let proof = vec![scheme.hash_pair(&Keccak, sorted[2], sorted[3]).unwrap()];
+            assert_eq!(
+                verify(proof, tree.root(), forged, scheme, &Keccak).unwrap(),
+                scheme == HashScheme::Legacy
+            );
+        }
+    }
+
+    #[test]
+    fn multi_proofs_verify_for_every_subset() {
+        for scheme in SCHEMES {
+            for n in 1..=7 {
+                let leaves = leaves(n);
+                let tree = MerkleTree::new(leaves.clone(), scheme, Keccak).unwrap();
+                for mask in 1u32..(1 << n) {
+                    let subset: Vec<[u8; 32]> = leaves
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| mask & (1 << i) != 0)
+                        .map(|(_, leaf)| *leaf)
+                        .collect();
+                    let multi_proof = tree.multi_proof(&subset).unwrap();
+                    assert_eq!(multi_proof.leaves.len(), subset.len());
+                    assert!(verify_multi(
+                        &multi_proof.leaves,
+                        &multi_proof.proof,
+                        &multi_proof.proof_flags,
+                        tree.root(),
+                        scheme,
+                        &Keccak
+                    )
+                    .unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multi_proof_shares_siblings() {
+        let leaves = leaves(16);
+        let tree = MerkleTree::new(leaves.clone(), HashScheme::Legacy, Keccak).unwrap();
+        let multi_proof = tree.multi_proof(&leaves[..8]).unwrap();
+        let single_proofs: usize = leaves[..8]
+            .iter()
+            .map(|leaf| tree.proof(leaf).unwrap().len())
+            .sum();
+        assert!(multi_proof.proof.len() < single_proofs);
+    }
+
+    #[test]
+    fn tampered_multi_proofs_fail() {
+        let leaves = leaves(6);
+        let tree = MerkleTree::new(leaves.clone(), HashScheme::Legacy, Keccak).unwrap();
+        let multi_proof = tree.multi_proof(&leaves[1..4]).unwrap();
+        let verify = |leaves: &[[u8; 32]], proof: &[[u8; 32]]| {
+            verify_multi(
+                leaves,
+                proof,
+                &multi_proof.proof_flags,
+                tree.root(),
+                HashScheme::Legacy,
+                &Keccak,
+            )
+            .unwrap()
+        };
+
+        let mut forged = multi_proof.leaves.clone();
+        forged[0] = [7; 32];
+        assert!(!verify(&forged, &multi_proof.proof));
+
+        let mut reordered = multi_proof.leaves.clone();
+        reordered.swap(0, 2);
+        assert!(!verify(&reordered, &multi_proof.proof));
+
+        assert!(!verify(&multi_proof.leaves, &multi_proof.proof[1..]));
+        assert!(!verify(&[], &[]));
+        assert!(tree.multi_proof(&[[7; 32]]).is_none());
+    }
+}
@@ -23,3 +23,64 @@ export const findClaimStatusKey = (
     PROGRAM_ID
   );
 };
+
+export const findReboundClaimStatusKey = (
+  claimant: PublicKey,
+  distributor: PublicKey
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [
+      utils.bytes.utf8.encode("ReboundClaimStatus"),
+      distributor.toBytes(),
+      claimant.toBytes(),
+    ],
+    PROGRAM_ID
+  );
+};
+
+export const findClaimTargetKey = (
+  distributor: PublicKey,
+  targetProgram: PublicKey
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [
+      utils.bytes.utf8.encode("ClaimTarget"),
+      distributor.toBytes(),
+      targetProgram.toBytes(),
+    ],
+    PROGRAM_ID
+  );
+};
+
+export const findClaimDelegateKey = (
+  claimant: PublicKey
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [utils.bytes.utf8.encode("ClaimDelegate"), claimant.toBytes()],
+    PROGRAM_ID
+  );
+};
+
+export const findClaimRebindKey = (
+  distributor: PublicKey,
+  newClaimant: PublicKey
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [
+      utils.bytes.utf8.encode("ClaimRebind"),
+      distributor.toBytes(),
+      newClaimant.toBytes(),
+    ],
+    PROGRAM_ID
+  );
+};
+
+export const findScreeningFlagKey = (
+  claimant: PublicKey,
+  screeningProgram: PublicKey
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [utils.bytes.utf8.encode("flagged"), claimant.toBytes()],
+    screeningProgram
+  );
+};
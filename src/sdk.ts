@@ -1,9 +1,25 @@
 import * as anchor from "@coral-xyz/anchor";
-import type { PublicKey } from "@solana/web3.js";
+import {
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+  TransactionSignature,
+  SystemProgram,
+} from "@solana/web3.js";
+import * as spl from "@solana/spl-token";
 import { PROGRAM_ID } from "./constants";
 import { MerkleDistributor } from "../target/types/merkle_distributor";
 import idl from "../target/idl/merkle_distributor.json";
-import type { CreateDistributorArgs, Distributor } from "./types";
+import { findClaimDelegateKey, findClaimStatusKey } from "./pda";
+import type {
+  ClaimDelegate,
+  ClaimManyArgs,
+  CreateDistributorArgs,
+  Distributor,
+  SetClaimDelegateArgs,
+} from "./types";
+import { toBytes32Array, processTransaction } from "./utils";
 import { MerkleDistributorWrapper } from "./wrapper";
 
 export class MerkleDistributorSDK {
@@ -52,4 +68,188 @@ export class MerkleDistributorSDK {
       ...args,
     });
   }
+
+  /**
+   * Migrate a distributor created under an older account layout to the current one.
+   * Distributors that were not migrated cannot be loaded.
+   * @returns {TransactionSignature}
+   */
+  async migrateDistributor(
+    key: PublicKey,
+    adminAuth: Keypair
+  ): Promise<TransactionSignature> {
+    const ix = this.program.instruction.migrateDistributor({
+      accounts: {
+        adminAuth: adminAuth.publicKey,
+        distributor: key,
+        payer: this.provider.publicKey,
+        systemProgram: SystemProgram.programId,
+      },
+    });
+
+    return processTransaction(this.provider, new Transaction().add(ix), [
+      adminAuth,
+    ]);
+  }
+
+  /**
+   * Migrate a claim status created under an older account layout to the current one.
+   * Claim statuses that were not migrated cannot be claimed, revoked or rebound.
+   * @returns {TransactionSignature}
+   */
+  async migrateClaimStatus(
+    distributor: PublicKey,
+    claimant: PublicKey
+  ): Promise<TransactionSignature> {
+    const ix = this.program.instruction.migrateClaimStatus({
+      accounts: {
+        claimStatus: findClaimStatusKey(claimant, distributor)[0],
+        payer: this.provider.publicKey,
+        systemProgram: SystemProgram.programId,
+      },
+    });
+
+    return processTransaction(this.provider, new Transaction().add(ix));
+  }
+
+  /**
+   * Allow a delegate to claim on behalf of the claimant from any distributor.
+   * @returns {TransactionSignature}
+   */
+  async setClaimDelegate(
+    args: SetClaimDelegateArgs
+  ): Promise<TransactionSignature> {
+    const { claimant, delegate, destination } = args;
+    const [claimDelegate] = findClaimDelegateKey(claimant.publicKey);
+
+    const ix = this.program.instruction.setClaimDelegate(
+      delegate,
+      destination ?? null,
+      {
+        accounts: {
+          claimant: claimant.publicKey,
+          claimDelegate,
+          systemProgram: SystemProgram.programId,
+        },
+      }
+    );
+
+    return processTransaction(this.provider, new Transaction().add(ix), [
+      claimant,
+    ]);
+  }
+
+  /**
+   * Remove the claimant's delegate.
+   * @returns {TransactionSignature}
+   */
+  async removeClaimDelegate(claimant: Keypair): Promise<TransactionSignature> {
+    const [claimDelegate] = findClaimDelegateKey(claimant.publicKey);
+
+    const ix = this.program.instruction.removeClaimDelegate({
+      accounts: {
+        claimant: claimant.publicKey,
+        claimDelegate,
+      },
+    });
+
+    return processTransaction(this.provider, new Transaction().add(ix), [
+      claimant,
+    ]);
+  }
+
+  /**
+   * Fetch the claimant's delegate, null if it has none.
+   * @returns {ClaimDelegate | null}
+   */
+  async getClaimDelegate(claimant: PublicKey): Promise<ClaimDelegate | null> {
+    const [key] = findClaimDelegateKey(claimant);
+    return this.program.account.claimDelegate.fetchNullable(key);
+  }
+
+  /**
+   * Claim from several distributors in a single instruction.
+   * @returns {TransactionInstruction}
+   */
+  claimManyIx(args: ClaimManyArgs): TransactionInstruction {
+    const { claimant, entries } = args;
+
+    return this.program.instruction.claimMany(
+      entries.map((e) => ({
+        index: e.index,
+        amount: e.amount,
+        terms: e.terms ?? null,
+        proofLen: e.proof.length,
+      })),
+      entries.flatMap((e) => e.proof.map((p) => toBytes32Array(p))),
+      {
+        accounts: {
+          claimant,
+          payer: claimant,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: spl.TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts: entries.flatMap((e) => [
+          { pubkey: e.distributor.key, isSigner: false, isWritable: true },
+          {
+            pubkey: findClaimStatusKey(claimant, e.distributor.key)[0],
+            isSigner: false,
+            isWritable: true,
+          },
+          {
+            pubkey: e.distributor.distributorATA,
+            isSigner: false,
+            isWritable: true,
+          },
+          {
+            pubkey: spl.getAssociatedTokenAddressSync(
+              e.distributor.data.mint,
+              claimant
+            ),
+            isSigner: false,
+            isWritable: true,
+          },
+        ]),
+      }
+    );
+  }
+
+  /**
+   * Claim from several distributors in a single transaction, creating missing token accounts.
+   * @returns {TransactionSignature | Transaction}
+   */
+  async claimMany(
+    args: ClaimManyArgs,
+    returnTx: boolean = false
+  ): Promise<TransactionSignature | Transaction> {
+    const tx = new Transaction();
+
+    const mints = [
+      ...new Set(args.entries.map((e) => e.distributor.data.mint.toBase58())),
+    ];
+    for (const mint of mints) {
+      const mintKey = new PublicKey(mint);
+      const address = spl.getAssociatedTokenAddressSync(mintKey, args.claimant);
+      try {
+        await spl.getAccount(this.provider.connection, address);
+      } catch (e) {
+        tx.add(
+          spl.createAssociatedTokenAccountInstruction(
+            this.provider.publicKey,
+            address,
+            args.claimant,
+            mintKey
+          )
+        );
+      }
+    }
+
+    tx.add(this.claimManyIx(args));
+
+    if (returnTx) {
+      return tx;
+    } else {
+      return processTransaction(this.provider, tx, args.signers);
+    }
+  }
 }
@@ -2,6 +2,28 @@ import * as anchor from "@coral-xyz/anchor";
 import type { Keypair, PublicKey } from "@solana/web3.js";
 
 import type { MerkleDistributorSDK } from "./sdk";
+import type { MerkleDistributorWrapper } from "./wrapper";
+import type {
+  HashFunction,
+  HashScheme,
+  LeafTerms,
+  LeafVersion,
+  ProofFlag,
+} from "./utils";
+
+/**
+ * How the early claim ramp applies to staked claims.
+ */
+export type StakeClaimScaling =
+  | { full: {} }
+  | { scaled: {} }
+  | { scaledWithStakeBase: {} };
+
+export const STAKE_CLAIM_SCALING_FULL: StakeClaimScaling = { full: {} };
+export const STAKE_CLAIM_SCALING_SCALED: StakeClaimScaling = { scaled: {} };
+export const STAKE_CLAIM_SCALING_SCALED_WITH_STAKE_BASE: StakeClaimScaling = {
+  scaledWithStakeBase: {},
+};
 
 export type CreateDistributorArgs = {
   sdk: MerkleDistributorSDK;
@@ -16,6 +38,13 @@ export type CreateDistributorArgs = {
   stakeClaimOnly: boolean;
   immediateClaimPercentage: anchor.BN;
   laterClaimOffsetSeconds: anchor.BN;
+  stakeClaimScaling?: StakeClaimScaling;
+  stakeImmediateClaimPercentage?: anchor.BN;
+  hashScheme?: HashScheme;
+  leafVersion?: LeafVersion;
+  hashFunction?: HashFunction;
+  // Distribute lamports held by the distributor account, `tokenMint` must be the native mint.
+  native?: boolean;
 };
 
 export type UpdateDistributorArgs = {
@@ -37,6 +66,27 @@ export type UpdateDistributorClaimPercentageArgs = {
   adminAuth: Keypair;
 };
 
+export type UpdateDistributorStakeClaimScalingArgs = {
+  stakeClaimScaling: StakeClaimScaling;
+  stakeImmediateClaimPercentage: anchor.BN;
+  adminAuth: Keypair;
+};
+
+export type UpdateDistributorGracePeriodArgs = {
+  gracePeriodSeconds: anchor.BN;
+  graceClaimPercentage: anchor.BN;
+  adminAuth: Keypair;
+};
+
+export type AddClaimTargetArgs = {
+  targetProgram: PublicKey;
+  // Instruction data prefix the forwarded instruction must start with, at most 8 bytes.
+  ixDiscriminator: Buffer;
+  // Offset in the forwarded instruction data of the u64 amount.
+  amountOffset: number;
+  adminAuth: Keypair;
+};
+
 export type Distributor = {
   bump: number;
   base: PublicKey;
@@ -47,9 +97,92 @@ export type Distributor = {
 export type ClaimArgs = {
   index: anchor.BN;
   amount: anchor.BN;
+  // Required by distributors with `LEAF_VERSION_V2` leaves.
+  terms?: LeafTerms;
   proof: Buffer[];
   claimant: PublicKey;
   signers?: Keypair[];
+  // Original claimant of the leaf, set when `claimant` claims a leaf rebound to
+  // it with `rebindClaim`.
+  rebound?: PublicKey;
+};
+
+export type RebindClaimArgs = {
+  index: anchor.BN;
+  amount: anchor.BN;
+  // Required by distributors with `LEAF_VERSION_V2` leaves.
+  terms?: LeafTerms;
+  proof: Buffer[];
+  claimant: Keypair;
+  newClaimant: PublicKey;
+};
+
+export type ClaimAsDelegateArgs = {
+  index: anchor.BN;
+  amount: anchor.BN;
+  // Required by distributors with `LEAF_VERSION_V2` leaves.
+  terms?: LeafTerms;
+  proof: Buffer[];
+  claimant: PublicKey;
+  delegate: Keypair;
+};
+
+export type SetClaimDelegateArgs = {
+  claimant: Keypair;
+  delegate: PublicKey;
+  // Owner of the token accounts delegated claims go to; the claimant if unset.
+  destination?: PublicKey;
+};
+
+export type UpdateDistributorRateLimitsArgs = {
+  // Zero disables rate limits.
+  rateLimitWindowSeconds: anchor.BN;
+  // Zero for no limit.
+  maxClaimPerWindow: anchor.BN;
+  // Zero for no limit.
+  maxClaimantClaimPerWindow: anchor.BN;
+  adminAuth: Keypair;
+};
+
+export type RevokeClaimArgs = {
+  index: anchor.BN;
+  amount: anchor.BN;
+  // Required by distributors with `LEAF_VERSION_V2` leaves.
+  terms?: LeafTerms;
+  proof: Buffer[];
+  claimant: PublicKey;
+  adminAuth: Keypair;
+  // Token account to return the revoked amount to; kept in the vault if unset.
+  returnTo?: PublicKey;
+};
+
+export type ClaimManyEntry = {
+  distributor: MerkleDistributorWrapper;
+  index: anchor.BN;
+  amount: anchor.BN;
+  terms?: LeafTerms;
+  proof: Buffer[];
+};
+
+export type ClaimManyArgs = {
+  entries: ClaimManyEntry[];
+  claimant: PublicKey;
+  signers?: Keypair[];
+};
+
+export type DistributeBatchEntry = {
+  claimant: PublicKey;
+  index: anchor.BN;
+  amount: anchor.BN;
+  terms?: LeafTerms;
+};
+
+export type DistributeBatchArgs = {
+  // Entries in tree order, see `BalanceTree.getMultiProof`.
+  entries: DistributeBatchEntry[];
+  proof: Buffer[];
+  proofFlags: ProofFlag[];
+  adminAuth: Keypair;
 };
 
 export interface DistributorData {
@@ -67,10 +200,53 @@ export interface DistributorData {
   stakeClaimOnly: boolean;
   immediateClaimPercentage: anchor.BN;
   laterClaimOffsetSeconds: anchor.BN;
+  stakeClaimScaling: StakeClaimScaling;
+  stakeImmediateClaimPercentage: anchor.BN;
+  hashScheme: HashScheme;
+  leafVersion: LeafVersion;
+  hashFunction: HashFunction;
+  version: number;
+  sweepRecipient: PublicKey;
+  gracePeriodSeconds: anchor.BN;
+  graceClaimPercentage: anchor.BN;
+  screeningProgram: PublicKey;
+  rateLimitWindowSeconds: anchor.BN;
+  maxClaimPerWindow: anchor.BN;
+  maxClaimantClaimPerWindow: anchor.BN;
+  rateLimitWindow: RateLimitWindow;
+  native: boolean;
+  reserved: Array<number>;
 }
 
 export interface ClaimStatus {
   claimant: PublicKey;
   claimedAt: anchor.BN;
   claimedAmount: anchor.BN;
+  index: anchor.BN;
+  root: Array<number>;
+  numClaims: anchor.BN;
+  payer: PublicKey;
+  revokedAt: anchor.BN;
+  rateLimitWindow: RateLimitWindow;
+  reboundTo: PublicKey;
+  reserved: Array<anchor.BN>;
+}
+
+export interface ClaimRebind {
+  distributor: PublicKey;
+  claimant: PublicKey;
+  newClaimant: PublicKey;
+  bump: number;
+}
+
+export interface ClaimDelegate {
+  claimant: PublicKey;
+  delegate: PublicKey;
+  destination: PublicKey;
+  bump: number;
+}
+
+export interface RateLimitWindow {
+  startTs: anchor.BN;
+  claimedAmount: anchor.BN;
 }
@@ -1,17 +1,92 @@
 import * as anchor from "@coral-xyz/anchor";
 import type { PublicKey } from "@solana/web3.js";
 import type BN from "bn.js";
-import { keccak_256 } from "js-sha3";
+import invariant from "tiny-invariant";
 
-import { MerkleTree } from "./merkle-tree";
+import {
+  HASH_FUNCTION_KECCAK,
+  HASH_SCHEME_LEGACY,
+  HashFunction,
+  HashScheme,
+  MerkleTree,
+  ProofFlag,
+  hashLeaf,
+} from "./merkle-tree";
+
+export type BalanceLeaf = {
+  index: number;
+  account: PublicKey;
+  amount: BN;
+  terms?: LeafTerms;
+};
+
+/**
+ * Layout of the data hashed into a leaf, see `LeafVersion` in the program.
+ */
+export type LeafVersion = { v0: {} } | { v1: {} } | { v2: {} };
+
+export const LEAF_VERSION_V0: LeafVersion = { v0: {} };
+export const LEAF_VERSION_V1: LeafVersion = { v1: {} };
+export const LEAF_VERSION_V2: LeafVersion = { v2: {} };
+
+/**
+ * Per-claimant vesting and lockup terms of a `LEAF_VERSION_V2` leaf.
+ */
+export type LeafTerms = {
+  unlockTs: BN;
+  vestingDurationSeconds: BN;
+  stakeDurationEpochs: number;
+  category: number;
+};
+
+/**
+ * The distributor a `LEAF_VERSION_V1` tree is bound to: the base key it is
+ * derived from and the mint it distributes.
+ */
+export type LeafBinding = { base: PublicKey; mint: PublicKey };
 
 export class BalanceTree {
   private readonly _tree: MerkleTree;
-  constructor(balances: { account: PublicKey; amount: BN }[]) {
+  readonly hashScheme: HashScheme;
+  readonly hashFunction: HashFunction;
+  readonly binding?: LeafBinding;
+  readonly leafVersion: LeafVersion;
+  // Every balance carries terms, or none does. Terms require a binding.
+  constructor(
+    balances: { account: PublicKey; amount: BN; terms?: LeafTerms }[],
+    hashScheme: HashScheme = HASH_SCHEME_LEGACY,
+    binding?: LeafBinding,
+    hashFunction: HashFunction = HASH_FUNCTION_KECCAK
+  ) {
+    const withTerms = balances.filter((b) => b.terms).length;
+    invariant(
+      withTerms === 0 || withTerms === balances.length,
+      "either all or no balances must have terms"
+    );
+    invariant(withTerms === 0 || binding, "leaf terms require a binding");
+
+    this.hashScheme = hashScheme;
+    this.hashFunction = hashFunction;
+    this.binding = binding;
+    this.leafVersion = !binding
+      ? LEAF_VERSION_V0
+      : withTerms === 0
+      ? LEAF_VERSION_V1
+      : LEAF_VERSION_V2;
     this._tree = new MerkleTree(
-      balances.map(({ account, amount }, index) => {
-        return BalanceTree.toNode(index, account, amount);
-      })
+      balances.map(({ account, amount, terms }, index) => {
+        return BalanceTree.toNode(
+          index,
+          account,
+          amount,
+          hashScheme,
+          binding,
+          terms,
+          hashFunction
+        );
+      }),
+      hashScheme,
+      hashFunction
     );
   }
 
@@ -20,24 +95,90 @@ export class BalanceTree {
     account: PublicKey,
     amount: BN,
     proof: Buffer[],
-    root: Buffer
+    root: Buffer,
+    hashScheme: HashScheme = HASH_SCHEME_LEGACY,
+    binding?: LeafBinding,
+    terms?: LeafTerms,
+    hashFunction: HashFunction = HASH_FUNCTION_KECCAK
   ): boolean {
-    let pair = BalanceTree.toNode(index, account, amount);
+    let pair = BalanceTree.toNode(
+      index,
+      account,
+      amount,
+      hashScheme,
+      binding,
+      terms,
+      hashFunction
+    );
     for (const item of proof) {
-      pair = MerkleTree.combinedHash(pair, item);
+      pair = MerkleTree.combinedHash(pair, item, hashScheme, hashFunction);
     }
 
     return pair.equals(root);
   }
 
-  // keccak256(abi.encode(index, account, amount))
-  static toNode(index: number, account: PublicKey, amount: BN): Buffer {
-    const buf = Buffer.concat([
+  // keccak256(abi.encode(index, account, amount)), tagged per hash scheme
+  // bound to a distributor: keccak256(1, index, account, amount, base, mint)
+  // with terms: keccak256(2, index, account, amount, base, mint, ...terms)
+  static toNode(
+    index: number,
+    account: PublicKey,
+    amount: BN,
+    hashScheme: HashScheme = HASH_SCHEME_LEGACY,
+    binding?: LeafBinding,
+    terms?: LeafTerms,
+    hashFunction: HashFunction = HASH_FUNCTION_KECCAK
+  ): Buffer {
+    const fields = [
       new anchor.BN(index).toArrayLike(Buffer, "le", 8),
       account.toBuffer(),
       new anchor.BN(amount).toArrayLike(Buffer, "le", 8),
-    ]);
-    return Buffer.from(keccak_256(buf), "hex");
+    ];
+    if (!binding) {
+      return hashLeaf(Buffer.concat(fields), hashScheme, hashFunction);
+    }
+
+    const bound = [...fields, binding.base.toBuffer(), binding.mint.toBuffer()];
+    if (!terms) {
+      return hashLeaf(
+        Buffer.concat([Buffer.from([1]), ...bound]),
+        hashScheme,
+        hashFunction
+      );
+    }
+    return hashLeaf(
+      Buffer.concat([
+        Buffer.from([2]),
+        ...bound,
+        new anchor.BN(terms.unlockTs).toArrayLike(Buffer, "le", 8),
+        new anchor.BN(terms.vestingDurationSeconds).toArrayLike(
+          Buffer,
+          "le",
+          8
+        ),
+        new anchor.BN(terms.stakeDurationEpochs).toArrayLike(Buffer, "le", 4),
+        Buffer.from([terms.category]),
+      ]),
+      hashScheme,
+      hashFunction
+    );
+  }
+
+  toNode(
+    index: number,
+    account: PublicKey,
+    amount: BN,
+    terms?: LeafTerms
+  ): Buffer {
+    return BalanceTree.toNode(
+      index,
+      account,
+      amount,
+      this.hashScheme,
+      this.binding,
+      terms,
+      this.hashFunction
+    );
   }
 
   getHexRoot(): string {
@@ -45,15 +186,55 @@ export class BalanceTree {
   }
 
   // returns the hex bytes32 values of the proof
-  getHexProof(index: number, account: PublicKey, amount: BN): string[] {
-    return this._tree.getHexProof(BalanceTree.toNode(index, account, amount));
+  getHexProof(
+    index: number,
+    account: PublicKey,
+    amount: BN,
+    terms?: LeafTerms
+  ): string[] {
+    return this._tree.getHexProof(this.toNode(index, account, amount, terms));
   }
 
   getRoot(): Buffer {
     return this._tree.getRoot();
   }
 
-  getProof(index: number, account: PublicKey, amount: BN): Buffer[] {
-    return this._tree.getProof(BalanceTree.toNode(index, account, amount));
+  getProof(
+    index: number,
+    account: PublicKey,
+    amount: BN,
+    terms?: LeafTerms
+  ): Buffer[] {
+    return this._tree.getProof(this.toNode(index, account, amount, terms));
+  }
+
+  // returns the leaves reordered to tree order, as the multiproof requires
+  getMultiProof(leaves: BalanceLeaf[]): {
+    leaves: BalanceLeaf[];
+    proof: Buffer[];
+    proofFlags: ProofFlag[];
+  } {
+    const byNode = new Map(
+      leaves.map((leaf) => [
+        this.toNode(
+          leaf.index,
+          leaf.account,
+          leaf.amount,
+          leaf.terms
+        ).toString("hex"),
+        leaf,
+      ])
+    );
+    const multiProof = this._tree.getMultiProof(
+      leaves.map((leaf) =>
+        this.toNode(leaf.index, leaf.account, leaf.amount, leaf.terms)
+      )
+    );
+
+    return {
+      leaves: multiProof.leaves.map((node) => byNode.get(node.toString("hex"))),
+      proof: multiProof.proof,
+      proofFlags: multiProof.proofFlags,
+    };
   }
 }
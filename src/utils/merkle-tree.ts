@@ -1,3 +1,4 @@
+import { sha256 } from "@noble/hashes/sha256";
 import { keccak_256 } from "js-sha3";
 import invariant from "tiny-invariant";
 
@@ -27,6 +28,69 @@ function bufArrToHexArr(arr: Buffer[]): string[] {
   return arr.map((el) => "0x" + el.toString("hex"));
 }
 
+/**
+ * How leaves and internal nodes are hashed, see `HashScheme` in the program.
+ */
+export type HashScheme =
+  | { legacy: {} }
+  | { tagged: {} }
+  | { taggedDoubleHashed: {} };
+
+export const HASH_SCHEME_LEGACY: HashScheme = { legacy: {} };
+export const HASH_SCHEME_TAGGED: HashScheme = { tagged: {} };
+export const HASH_SCHEME_TAGGED_DOUBLE_HASHED: HashScheme = {
+  taggedDoubleHashed: {},
+};
+
+/**
+ * Hash function of a tree, see `HashFunction` in the program.
+ * Poseidon trees have to be built with the Rust `MerkleTree`.
+ */
+export type HashFunction = { keccak: {} } | { sha256: {} } | { poseidon: {} };
+
+export const HASH_FUNCTION_KECCAK: HashFunction = { keccak: {} };
+export const HASH_FUNCTION_SHA256: HashFunction = { sha256: {} };
+export const HASH_FUNCTION_POSEIDON: HashFunction = { poseidon: {} };
+
+export function hash(data: Buffer, hashFunction: HashFunction): Buffer {
+  if ("sha256" in hashFunction) {
+    return Buffer.from(sha256(data));
+  }
+  invariant(!("poseidon" in hashFunction), "poseidon is not supported");
+  return Buffer.from(keccak_256.digest(data));
+}
+
+export const LEAF_TAG = Buffer.from([0]);
+export const NODE_TAG = Buffer.from([1]);
+
+export function hashLeaf(
+  data: Buffer,
+  scheme: HashScheme,
+  hashFunction: HashFunction = HASH_FUNCTION_KECCAK
+): Buffer {
+  if ("tagged" in scheme) {
+    return hash(Buffer.concat([LEAF_TAG, data]), hashFunction);
+  } else if ("taggedDoubleHashed" in scheme) {
+    return hash(
+      Buffer.concat([LEAF_TAG, hash(data, hashFunction)]),
+      hashFunction
+    );
+  }
+  return hash(data, hashFunction);
+}
+
+/**
+ * How the on-chain multiproof verifier obtains the sibling of the next node it hashes.
+ */
+export type ProofFlag = { proof: {} } | { queue: {} } | { promote: {} };
+
+export type MultiProof = {
+  // Proven leaves, in tree order.
+  leaves: Buffer[];
+  proof: Buffer[];
+  proofFlags: ProofFlag[];
+};
+
 function sortAndConcat(...args: Buffer[]): Buffer {
   return Buffer.concat([...args].sort(Buffer.compare.bind(null)));
 }
@@ -37,8 +101,16 @@ export class MerkleTree {
     [hexElement: string]: number;
   };
   private readonly _layers: Buffer[][];
+  readonly hashScheme: HashScheme;
+  readonly hashFunction: HashFunction;
 
-  constructor(elements: Buffer[]) {
+  constructor(
+    elements: Buffer[],
+    hashScheme: HashScheme = HASH_SCHEME_LEGACY,
+    hashFunction: HashFunction = HASH_FUNCTION_KECCAK
+  ) {
+    this.hashScheme = hashScheme;
+    this.hashFunction = hashFunction;
     this._elements = [...elements];
     // Sort elements
     this._elements.sort(Buffer.compare.bind(null));
@@ -79,14 +151,26 @@ export class MerkleTree {
       if (idx % 2 === 0) {
         // Hash the current element with its pair element
         const pairEl = arr[idx + 1];
-        layer.push(MerkleTree.combinedHash(el, pairEl));
+        layer.push(
+          MerkleTree.combinedHash(
+            el,
+            pairEl,
+            this.hashScheme,
+            this.hashFunction
+          )
+        );
       }
 
       return layer;
     }, []);
   }
 
-  static combinedHash(first: Buffer, second: Buffer | undefined): Buffer {
+  static combinedHash(
+    first: Buffer,
+    second: Buffer | undefined,
+    hashScheme: HashScheme = HASH_SCHEME_LEGACY,
+    hashFunction: HashFunction = HASH_FUNCTION_KECCAK
+  ): Buffer {
     if (!first) {
       invariant(second, "second element of pair must exist");
       return second;
@@ -96,7 +180,13 @@ export class MerkleTree {
       return first;
     }
 
-    return Buffer.from(keccak_256.digest(sortAndConcat(first, second)));
+    if ("legacy" in hashScheme) {
+      return hash(sortAndConcat(first, second), hashFunction);
+    }
+    return hash(
+      Buffer.concat([NODE_TAG, sortAndConcat(first, second)]),
+      hashFunction
+    );
   }
 
   getRoot(): Buffer {
@@ -130,6 +220,48 @@ export class MerkleTree {
     }, []);
   }
 
+  // OpenZeppelin-style multiproof, with an extra "promote" flag for the last node of odd layers.
+  getMultiProof(els: Buffer[]): MultiProof {
+    let known = [
+      ...new Set(
+        els.map((el) => {
+          const idx = this._bufferElementPositionIndex[el.toString("hex")];
+          if (typeof idx !== "number") {
+            throw new Error("Element does not exist in Merkle tree");
+          }
+          return idx;
+        })
+      ),
+    ].sort((a, b) => a - b);
+
+    const multiProof: MultiProof = {
+      leaves: known.map((idx) => this._elements[idx]),
+      proof: [],
+      proofFlags: [],
+    };
+
+    for (const layer of this._layers.slice(0, -1)) {
+      const parents: number[] = [];
+      for (let i = 0; i < known.length; i++) {
+        const idx = known[i];
+        const pairIdx = idx % 2 === 0 ? idx + 1 : idx - 1;
+        if (pairIdx >= layer.length) {
+          multiProof.proofFlags.push({ promote: {} });
+        } else if (known[i + 1] === pairIdx) {
+          multiProof.proofFlags.push({ queue: {} });
+          i++;
+        } else {
+          multiProof.proof.push(layer[pairIdx]);
+          multiProof.proofFlags.push({ proof: {} });
+        }
+        parents.push(Math.floor(idx / 2));
+      }
+      known = parents;
+    }
+
+    return multiProof;
+  }
+
   getHexProof(el: Buffer): string[] {
     const proof = this.getProof(el);
 
@@ -10,19 +10,42 @@ import {
 } from "@solana/web3.js";
 import * as spl from "@solana/spl-token";
 
-import { findClaimStatusKey, findDistributorKey } from "./pda";
+import {
+  findClaimDelegateKey,
+  findClaimRebindKey,
+  findClaimStatusKey,
+  findReboundClaimStatusKey,
+  findClaimTargetKey,
+  findDistributorKey,
+  findScreeningFlagKey,
+} from "./pda";
 import type { MerkleDistributorSDK } from "./sdk";
 import type {
+  AddClaimTargetArgs,
   ClaimArgs,
+  ClaimAsDelegateArgs,
   CreateDistributorArgs,
   Distributor,
   DistributorData,
   ClaimStatus,
+  DistributeBatchArgs,
   UpdateDistributorArgs,
   UpdateDistributorClaimWindowArgs,
   UpdateDistributorClaimPercentageArgs,
+  UpdateDistributorStakeClaimScalingArgs,
+  UpdateDistributorGracePeriodArgs,
+  UpdateDistributorRateLimitsArgs,
+  RevokeClaimArgs,
+  RebindClaimArgs,
 } from "./types";
-import { toBytes32Array, processTransaction } from "./utils";
+import { STAKE_CLAIM_SCALING_FULL } from "./types";
+import {
+  HASH_FUNCTION_KECCAK,
+  HASH_SCHEME_LEGACY,
+  LEAF_VERSION_V0,
+  toBytes32Array,
+  processTransaction,
+} from "./utils";
 
 export class MerkleDistributorWrapper {
   readonly program: anchor.Program<MerkleDistributor>;
@@ -55,12 +78,31 @@ export class MerkleDistributorWrapper {
     );
   }
 
-  getEstimatedClaimAmount(amount: number, nowSeconds: number): number {
-    const percentDiff =
-      100_000000 - this.data.immediateClaimPercentage.toNumber();
+  getEstimatedClaimAmount(
+    amount: number,
+    nowSeconds: number,
+    stake: boolean = false
+  ): number {
+    if (this.isInGracePeriod(nowSeconds)) {
+      return Math.floor(
+        amount * (this.data.graceClaimPercentage.toNumber() / 100_000000)
+      );
+    }
+
+    let basePercentage = this.data.immediateClaimPercentage.toNumber();
+    if (stake) {
+      const scaling = this.data.stakeClaimScaling;
+      if ("full" in scaling) {
+        return amount;
+      } else if ("scaledWithStakeBase" in scaling) {
+        basePercentage = this.data.stakeImmediateClaimPercentage.toNumber();
+      }
+    }
+
+    const percentDiff = 100_000000 - basePercentage;
 
     const scaledPercentage =
-      this.data.immediateClaimPercentage.toNumber() +
+      basePercentage +
       (percentDiff * (nowSeconds - this.data.claimStartTs.toNumber())) /
         this.data.laterClaimOffsetSeconds.toNumber();
 
@@ -69,6 +111,23 @@ export class MerkleDistributorWrapper {
     return Math.floor(scaledAmount);
   }
 
+  /**
+   * Last timestamp a claim is accepted at, the end of the claim window plus the grace period.
+   */
+  getClaimDeadline(): number {
+    return (
+      this.data.claimEndTs.toNumber() +
+      this.data.gracePeriodSeconds.toNumber()
+    );
+  }
+
+  isInGracePeriod(nowSeconds: number): boolean {
+    return (
+      nowSeconds > this.data.claimEndTs.toNumber() &&
+      nowSeconds <= this.getClaimDeadline()
+    );
+  }
+
   static async createDistributor(
     args: CreateDistributorArgs
   ): Promise<Distributor> {
@@ -92,6 +151,12 @@ export class MerkleDistributorWrapper {
         args.stakeClaimOnly,
         args.immediateClaimPercentage,
         args.laterClaimOffsetSeconds,
+        args.stakeClaimScaling ?? STAKE_CLAIM_SCALING_FULL,
+        args.stakeImmediateClaimPercentage ?? new anchor.BN(0),
+        args.hashScheme ?? HASH_SCHEME_LEGACY,
+        args.leafVersion ?? LEAF_VERSION_V0,
+        args.hashFunction ?? HASH_FUNCTION_KECCAK,
+        args.native ?? false,
         {
           accounts: {
             base: baseKey.publicKey,
@@ -122,7 +187,8 @@ export class MerkleDistributorWrapper {
       );
     }
 
-    if (instruction) {
+    // Native distributors hold their lamports themselves.
+    if (instruction && !args.native) {
       ixs.push(instruction);
     }
 
@@ -137,13 +203,41 @@ export class MerkleDistributorWrapper {
     };
   }
 
+  /**
+   * The claimant's flag account of the distributor's screening program, null if it has none.
+   */
+  screeningFlag(claimant: PublicKey): PublicKey | null {
+    if (this.data.screeningProgram.equals(PublicKey.default)) {
+      return null;
+    }
+    return findScreeningFlagKey(claimant, this.data.screeningProgram)[0];
+  }
+
+  /**
+   * The claimant's `ClaimRebind`, null unless it claims a rebound leaf.
+   */
+  rebind(args: ClaimArgs): PublicKey | null {
+    return args.rebound ? findClaimRebindKey(this.key, args.claimant)[0] : null;
+  }
+
+  /**
+   * Status of the claimed leaf, its own status if it was rebound to the
+   * claimant.
+   */
+  claimStatus(args: ClaimArgs): PublicKey {
+    return args.rebound
+      ? findReboundClaimStatusKey(args.rebound, this.key)[0]
+      : findClaimStatusKey(args.claimant, this.key)[0];
+  }
+
   claimIX(args: ClaimArgs): TransactionInstruction {
-    const { amount, claimant, index, proof } = args;
-    const [claimStatus, _] = findClaimStatusKey(claimant, this.key);
+    const { amount, claimant, index, proof, terms } = args;
+    const claimStatus = this.claimStatus(args);
 
     return this.program.instruction.claim(
       index,
       amount,
+      terms ?? null,
       proof.map((p) => toBytes32Array(p)),
       {
         accounts: {
@@ -155,6 +249,11 @@ export class MerkleDistributorWrapper {
           payer: claimant,
           systemProgram: SystemProgram.programId,
           tokenProgram: spl.TOKEN_PROGRAM_ID,
+          screeningFlag: this.screeningFlag(claimant),
+          rebind: this.rebind(args),
+          leafScreeningFlag: args.rebound
+            ? this.screeningFlag(args.rebound)
+            : null,
         },
       }
     );
@@ -174,12 +273,13 @@ export class MerkleDistributorWrapper {
     stakeAccName: string,
     stakeDurationEpochs: number
   ): TransactionInstruction {
-    const { amount, claimant, index, proof } = args;
-    const [claimStatus, _] = findClaimStatusKey(claimant, this.key);
+    const { amount, claimant, index, proof, terms } = args;
+    const claimStatus = this.claimStatus(args);
 
     return this.program.instruction.claimStake(
       index,
       amount,
+      terms ?? null,
       proof.map((p) => toBytes32Array(p)),
       bitToUse,
       stakeAccName,
@@ -200,6 +300,11 @@ export class MerkleDistributorWrapper {
           payer: claimant,
           systemProgram: SystemProgram.programId,
           tokenProgram: spl.TOKEN_PROGRAM_ID,
+          screeningFlag: this.screeningFlag(claimant),
+          rebind: this.rebind(args),
+          leafScreeningFlag: args.rebound
+            ? this.screeningFlag(args.rebound)
+            : null,
         },
       }
     );
@@ -256,6 +361,77 @@ export class MerkleDistributorWrapper {
     }
   }
 
+  /**
+   * Claims into a program approved with `addClaimTarget`.
+   * `targetIx` is the instruction to forward; its amount field is overwritten on-chain
+   * with the transferred amount.
+   */
+  claimIntoProgramIx(
+    args: ClaimArgs,
+    targetIx: TransactionInstruction
+  ): TransactionInstruction {
+    const { amount, claimant, index, proof, terms } = args;
+    const [claimStatus, _] = findClaimStatusKey(claimant, this.key);
+    const [claimTarget] = findClaimTargetKey(this.key, targetIx.programId);
+
+    return this.program.instruction.claimIntoProgram(
+      index,
+      amount,
+      terms ?? null,
+      proof.map((p) => toBytes32Array(p)),
+      targetIx.data,
+      {
+        accounts: {
+          distributor: this.key,
+          claimStatus,
+          claimTarget,
+          from: this.distributorATA,
+          to: spl.getAssociatedTokenAddressSync(this.data.mint, claimant),
+          targetProgram: targetIx.programId,
+          claimant,
+          payer: claimant,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: spl.TOKEN_PROGRAM_ID,
+          screeningFlag: this.screeningFlag(claimant),
+        },
+        remainingAccounts: targetIx.keys,
+      }
+    );
+  }
+
+  async claimIntoProgram(
+    args: ClaimArgs,
+    targetIx: TransactionInstruction,
+    returnTx: boolean = false
+  ): Promise<TransactionSignature | Transaction> {
+    const tx = new Transaction();
+
+    let address = spl.getAssociatedTokenAddressSync(
+      this.data.mint,
+      args.claimant
+    );
+    try {
+      await spl.getAccount(this.sdk.provider.connection, address);
+    } catch (e) {
+      tx.add(
+        spl.createAssociatedTokenAccountInstruction(
+          this.sdk.provider.publicKey,
+          address,
+          args.claimant,
+          this.data.mint
+        )
+      );
+    }
+
+    tx.add(this.claimIntoProgramIx(args, targetIx));
+
+    if (returnTx) {
+      return tx;
+    } else {
+      return processTransaction(this.sdk.provider, tx, args.signers);
+    }
+  }
+
   async claim(
     args: ClaimArgs,
     returnTx: boolean = false
@@ -291,11 +467,206 @@ export class MerkleDistributorWrapper {
     }
   }
 
+  /**
+   * Claims on behalf of a claimant that registered `delegate` with `setClaimDelegate`. The tokens
+   * go to the ATA of the claimant's approved destination.
+   */
+  claimAsDelegateIx(
+    args: ClaimAsDelegateArgs,
+    destination: PublicKey
+  ): TransactionInstruction {
+    const { amount, claimant, delegate, index, proof, terms } = args;
+    const [claimStatus] = findClaimStatusKey(claimant, this.key);
+
+    return this.program.instruction.claimAsDelegate(
+      index,
+      amount,
+      terms ?? null,
+      proof.map((p) => toBytes32Array(p)),
+      {
+        accounts: {
+          distributor: this.key,
+          claimDelegate: findClaimDelegateKey(claimant)[0],
+          claimStatus,
+          from: this.distributorATA,
+          to: spl.getAssociatedTokenAddressSync(this.data.mint, destination),
+          claimant,
+          delegate: delegate.publicKey,
+          payer: delegate.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: spl.TOKEN_PROGRAM_ID,
+          screeningFlag: this.screeningFlag(claimant),
+          destinationScreeningFlag: destination.equals(claimant)
+            ? null
+            : this.screeningFlag(destination),
+        },
+      }
+    );
+  }
+
+  async claimAsDelegate(
+    args: ClaimAsDelegateArgs,
+    returnTx: boolean = false
+  ): Promise<TransactionSignature | Transaction> {
+    const { provider } = this.sdk;
+
+    const claimDelegate = await this.sdk.getClaimDelegate(args.claimant);
+    if (!claimDelegate) {
+      throw new Error("Claimant has no claim delegate");
+    }
+    const { ixs } = await this.createAtaIxs(claimDelegate.destination);
+
+    const tx = new Transaction().add(
+      ...ixs,
+      this.claimAsDelegateIx(args, claimDelegate.destination)
+    );
+
+    if (returnTx) {
+      return tx;
+    } else {
+      return processTransaction(provider, tx, [args.delegate]);
+    }
+  }
+
+  /**
+   * Claims lamports from a native distributor into the claimant's wallet.
+   */
+  claimNativeIx(args: ClaimArgs): TransactionInstruction {
+    const { amount, claimant, index, proof, terms } = args;
+    const claimStatus = this.claimStatus(args);
+
+    return this.program.instruction.claimNative(
+      index,
+      amount,
+      terms ?? null,
+      proof.map((p) => toBytes32Array(p)),
+      {
+        accounts: {
+          distributor: this.key,
+          claimStatus,
+          claimant,
+          payer: claimant,
+          systemProgram: SystemProgram.programId,
+          screeningFlag: this.screeningFlag(claimant),
+          rebind: this.rebind(args),
+          leafScreeningFlag: args.rebound
+            ? this.screeningFlag(args.rebound)
+            : null,
+        },
+      }
+    );
+  }
+
+  async claimNative(
+    args: ClaimArgs,
+    returnTx: boolean = false
+  ): Promise<TransactionSignature | Transaction> {
+    const tx = new Transaction().add(this.claimNativeIx(args));
+
+    if (returnTx) {
+      return tx;
+    } else {
+      return processTransaction(this.sdk.provider, tx, args.signers);
+    }
+  }
+
+  /**
+   * Lamports a native distributor holds above its rent exempt minimum.
+   */
+  async getNativeVaultAmount(): Promise<anchor.BN> {
+    const { connection } = this.sdk.provider;
+    const info = await connection.getAccountInfo(this.key);
+    const rentExempt = await connection.getMinimumBalanceForRentExemption(
+      info.data.length
+    );
+    return new anchor.BN(Math.max(info.lamports - rentExempt, 0));
+  }
+
+  distributeBatchIx(args: DistributeBatchArgs): TransactionInstruction {
+    const { entries, adminAuth } = args;
+
+    return this.program.instruction.distributeBatch(
+      entries.map((e) => ({
+        index: e.index,
+        claimant: e.claimant,
+        amount: e.amount,
+        terms: e.terms ?? null,
+      })),
+      args.proof.map((p) => toBytes32Array(p)),
+      args.proofFlags,
+      {
+        accounts: {
+          adminAuth: adminAuth.publicKey,
+          distributor: this.key,
+          from: this.distributorATA,
+          payer: this.sdk.provider.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: spl.TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts: entries.flatMap((e) => [
+          {
+            pubkey: findClaimStatusKey(e.claimant, this.key)[0],
+            isSigner: false,
+            isWritable: true,
+          },
+          {
+            pubkey: spl.getAssociatedTokenAddressSync(
+              this.data.mint,
+              e.claimant
+            ),
+            isSigner: false,
+            isWritable: true,
+          },
+        ]),
+      }
+    );
+  }
+
+  /**
+   * Pushes tokens to the claimants of `entries`, creating their token accounts if needed.
+   */
+  async distributeBatch(
+    args: DistributeBatchArgs
+  ): Promise<TransactionSignature> {
+    const tx = new Transaction();
+
+    for (const { claimant } of args.entries) {
+      const address = spl.getAssociatedTokenAddressSync(
+        this.data.mint,
+        claimant
+      );
+      try {
+        await spl.getAccount(this.sdk.provider.connection, address);
+      } catch (e) {
+        tx.add(
+          spl.createAssociatedTokenAccountIdempotentInstruction(
+            this.sdk.provider.publicKey,
+            address,
+            claimant,
+            this.data.mint
+          )
+        );
+      }
+    }
+
+    tx.add(this.distributeBatchIx(args));
+
+    return processTransaction(this.sdk.provider, tx, [args.adminAuth]);
+  }
+
   async getClaimStatus(claimant: PublicKey): Promise<ClaimStatus> {
     const [key] = findClaimStatusKey(claimant, this.key);
     return this.program.account.claimStatus.fetch(key);
   }
 
+  /**
+   * Status of the leaf of `claimant` rebound to another wallet.
+   */
+  async getReboundClaimStatus(claimant: PublicKey): Promise<ClaimStatus> {
+    const [key] = findReboundClaimStatusKey(claimant, this.key);
+    return this.program.account.claimStatus.fetch(key);
+  }
+
   async reload(): Promise<void> {
     this.data = await this.program.account.merkleDistributor.fetch(this.key);
   }
@@ -374,15 +745,249 @@ export class MerkleDistributorWrapper {
     );
   }
 
-  async adminClaimAfterExpiry(
-    adminAuth: Keypair
+  async updateStakeClaimScaling(
+    args: UpdateDistributorStakeClaimScalingArgs
   ): Promise<TransactionSignature> {
     const ixs: TransactionInstruction[] = [];
 
-    let address = spl.getAssociatedTokenAddressSync(
-      this.data.mint,
-      adminAuth.publicKey
+    ixs.push(
+      this.sdk.program.instruction.updateDistributorStakeClaimScaling(
+        args.stakeClaimScaling,
+        args.stakeImmediateClaimPercentage,
+        {
+          accounts: {
+            adminAuth: args.adminAuth.publicKey,
+            distributor: this.key,
+          },
+        }
+      )
+    );
+
+    return processTransaction(
+      this.sdk.provider,
+      new Transaction().add(...ixs),
+      [args.adminAuth]
+    );
+  }
+
+  async updateGracePeriod(
+    args: UpdateDistributorGracePeriodArgs
+  ): Promise<TransactionSignature> {
+    const ixs: TransactionInstruction[] = [];
+
+    ixs.push(
+      this.sdk.program.instruction.updateDistributorGracePeriod(
+        args.gracePeriodSeconds,
+        args.graceClaimPercentage,
+        {
+          accounts: {
+            adminAuth: args.adminAuth.publicKey,
+            distributor: this.key,
+          },
+        }
+      )
+    );
+
+    return processTransaction(
+      this.sdk.provider,
+      new Transaction().add(...ixs),
+      [args.adminAuth]
+    );
+  }
+
+  async addClaimTarget(
+    args: AddClaimTargetArgs
+  ): Promise<TransactionSignature> {
+    const [claimTarget] = findClaimTargetKey(this.key, args.targetProgram);
+    const ixDiscriminator = Buffer.alloc(8);
+    args.ixDiscriminator.copy(ixDiscriminator);
+
+    const ix = this.sdk.program.instruction.addClaimTarget(
+      Array.from(ixDiscriminator),
+      args.ixDiscriminator.length,
+      args.amountOffset,
+      {
+        accounts: {
+          adminAuth: args.adminAuth.publicKey,
+          distributor: this.key,
+          claimTarget,
+          targetProgram: args.targetProgram,
+          payer: this.sdk.provider.publicKey,
+          systemProgram: SystemProgram.programId,
+        },
+      }
+    );
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      args.adminAuth,
+    ]);
+  }
+
+  async removeClaimTarget(
+    targetProgram: PublicKey,
+    adminAuth: Keypair
+  ): Promise<TransactionSignature> {
+    const [claimTarget] = findClaimTargetKey(this.key, targetProgram);
+
+    const ix = this.sdk.program.instruction.removeClaimTarget({
+      accounts: {
+        adminAuth: adminAuth.publicKey,
+        distributor: this.key,
+        claimTarget,
+      },
+    });
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      adminAuth,
+    ]);
+  }
+
+  /**
+   * Sets the rate limits of claims. Claims over a limit are capped to what the
+   * window still allows, or fail with `RateLimited` if it allows nothing more;
+   * `claimMany` and `distributeBatch` skip them with a `RateLimitTrippedEvent`
+   * instead. Zero disables a limit.
+   */
+  async updateRateLimits(
+    args: UpdateDistributorRateLimitsArgs
+  ): Promise<TransactionSignature> {
+    const ix = this.sdk.program.instruction.updateDistributorRateLimits(
+      args.rateLimitWindowSeconds,
+      args.maxClaimPerWindow,
+      args.maxClaimantClaimPerWindow,
+      {
+        accounts: {
+          adminAuth: args.adminAuth.publicKey,
+          distributor: this.key,
+        },
+      }
+    );
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      args.adminAuth,
+    ]);
+  }
+
+  /**
+   * Sets the program screening claimants, or disables screening if `screeningProgram` is
+   * `PublicKey.default`.
+   */
+  async updateScreeningProgram(
+    adminAuth: Keypair,
+    screeningProgram: PublicKey
+  ): Promise<TransactionSignature> {
+    const ix = this.sdk.program.instruction.updateDistributorScreeningProgram(
+      screeningProgram,
+      {
+        accounts: {
+          adminAuth: adminAuth.publicKey,
+          distributor: this.key,
+        },
+      }
+    );
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      adminAuth,
+    ]);
+  }
+
+  async revokeClaim(args: RevokeClaimArgs): Promise<TransactionSignature> {
+    const { amount, claimant, index, proof, terms } = args;
+    const [claimStatus, _] = findClaimStatusKey(claimant, this.key);
+    // A rebound leaf is revoked along with the claim status it is claimed
+    // through.
+    const reboundTo = (
+      await this.program.account.claimStatus.fetchNullable(claimStatus)
+    )?.reboundTo;
+    const reboundClaimStatus =
+      reboundTo && !reboundTo.equals(PublicKey.default)
+        ? findReboundClaimStatusKey(claimant, this.key)[0]
+        : null;
+
+    const ix = this.sdk.program.instruction.revokeClaim(
+      index,
+      amount,
+      terms ?? null,
+      proof.map((p) => toBytes32Array(p)),
+      {
+        accounts: {
+          adminAuth: args.adminAuth.publicKey,
+          distributor: this.key,
+          claimStatus,
+          claimant,
+          // Native distributors keep revoked lamports for `adminSweepNative`.
+          from: this.data.native ? null : this.distributorATA,
+          to: args.returnTo ?? null,
+          payer: this.sdk.provider.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: spl.TOKEN_PROGRAM_ID,
+          reboundClaimStatus,
+        },
+      }
     );
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      args.adminAuth,
+    ]);
+  }
+
+  /**
+   * Transfers the right to claim the claimant's leaf to `newClaimant`, which then claims it with
+   * `rebound` set to `claimant`.
+   */
+  async rebindClaim(args: RebindClaimArgs): Promise<TransactionSignature> {
+    const { amount, claimant, index, newClaimant, proof, terms } = args;
+
+    const ix = this.sdk.program.instruction.rebindClaim(
+      index,
+      amount,
+      terms ?? null,
+      proof.map((p) => toBytes32Array(p)),
+      {
+        accounts: {
+          distributor: this.key,
+          claimStatus: findClaimStatusKey(claimant.publicKey, this.key)[0],
+          reboundClaimStatus: findReboundClaimStatusKey(
+            claimant.publicKey,
+            this.key
+          )[0],
+          claimRebind: findClaimRebindKey(this.key, newClaimant)[0],
+          claimant: claimant.publicKey,
+          newClaimant,
+          payer: claimant.publicKey,
+          systemProgram: SystemProgram.programId,
+          screeningFlag: this.screeningFlag(claimant.publicKey),
+        },
+      }
+    );
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      claimant,
+    ]);
+  }
+
+  /**
+   * Amount of a vault holding `vaultAmount` the admin may sweep at `nowSeconds`.
+   */
+  getSweepableAmount(vaultAmount: anchor.BN, nowSeconds: number): anchor.BN {
+    if (nowSeconds > this.getClaimDeadline()) {
+      return vaultAmount;
+    }
+    const zero = new anchor.BN(0);
+    const outstanding = anchor.BN.max(
+      this.data.maxTotalClaim.sub(this.data.totalAmountClaimed),
+      zero
+    );
+    return anchor.BN.max(vaultAmount.sub(outstanding), zero);
+  }
+
+  private async createAtaIxs(owner: PublicKey): Promise<{
+    address: PublicKey;
+    ixs: TransactionInstruction[];
+  }> {
+    const ixs: TransactionInstruction[] = [];
+
+    let address = spl.getAssociatedTokenAddressSync(this.data.mint, owner);
     try {
       await spl.getAccount(this.sdk.provider.connection, address);
     } catch (e) {
@@ -390,12 +995,20 @@ export class MerkleDistributorWrapper {
         spl.createAssociatedTokenAccountInstruction(
           this.sdk.provider.publicKey,
           address,
-          adminAuth.publicKey,
+          owner,
           this.data.mint
         )
       );
     }
 
+    return { address, ixs };
+  }
+
+  async adminClaimAfterExpiry(
+    adminAuth: Keypair
+  ): Promise<TransactionSignature> {
+    const { address, ixs } = await this.createAtaIxs(adminAuth.publicKey);
+
     ixs.push(
       this.sdk.program.instruction.adminClaimAfterExpiry({
         accounts: {
@@ -414,4 +1027,88 @@ export class MerkleDistributorWrapper {
       [adminAuth]
     );
   }
+
+  /**
+   * Sweeps `amount` out of the vault to the admin, see `getSweepableAmount`.
+   */
+  async adminSweep(
+    adminAuth: Keypair,
+    amount: anchor.BN
+  ): Promise<TransactionSignature> {
+    const { address, ixs } = await this.createAtaIxs(adminAuth.publicKey);
+
+    ixs.push(
+      this.sdk.program.instruction.adminSweep(amount, {
+        accounts: {
+          distributor: this.key,
+          from: this.distributorATA,
+          to: address,
+          adminAuth: adminAuth.publicKey,
+          tokenProgram: spl.TOKEN_PROGRAM_ID,
+        },
+      })
+    );
+
+    return processTransaction(
+      this.sdk.provider,
+      new Transaction().add(...ixs),
+      [adminAuth]
+    );
+  }
+
+  /**
+   * Sweeps `amount` lamports out of a native distributor to the admin.
+   */
+  async adminSweepNative(
+    adminAuth: Keypair,
+    amount: anchor.BN
+  ): Promise<TransactionSignature> {
+    const ix = this.sdk.program.instruction.adminSweepNative(amount, {
+      accounts: {
+        distributor: this.key,
+        adminAuth: adminAuth.publicKey,
+      },
+    });
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      adminAuth,
+    ]);
+  }
+
+  /**
+   * Sets the token account `sweepAfterExpiry` sends the vault to, or unsets it if null.
+   * Native distributors take no recipient, see `adminSweepNative`.
+   */
+  async setSweepRecipient(
+    adminAuth: Keypair,
+    sweepRecipient: PublicKey | null
+  ): Promise<TransactionSignature> {
+    const ix = this.sdk.program.instruction.setSweepRecipient({
+      accounts: {
+        adminAuth: adminAuth.publicKey,
+        distributor: this.key,
+        sweepRecipient,
+      },
+    });
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix), [
+      adminAuth,
+    ]);
+  }
+
+  /**
+   * Sends the vault to the sweep recipient once the claim window is over. Anyone can call this.
+   */
+  async sweepAfterExpiry(): Promise<TransactionSignature> {
+    const ix = this.sdk.program.instruction.sweepAfterExpiry({
+      accounts: {
+        distributor: this.key,
+        from: this.distributorATA,
+        to: this.data.sweepRecipient,
+        tokenProgram: spl.TOKEN_PROGRAM_ID,
+      },
+    });
+
+    return processTransaction(this.sdk.provider, new Transaction().add(ix));
+  }
 }
@@ -0,0 +1,253 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import {
+  createAccount,
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+
+import { BalanceTree, processTransaction } from "../src/utils";
+import {
+  makeSDK,
+  createAndSeedDistributor,
+  DEFAULT_TOKEN_DECIMALS,
+} from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(1);
+const MAX_TOTAL_CLAIM = new anchor.BN(100);
+
+/** Swaps the account `from` of `ix` for `to`. */
+const spoof = (
+  ix: TransactionInstruction,
+  from: PublicKey,
+  to: PublicKey
+): TransactionInstruction => {
+  const key = ix.keys.find((k) => k.pubkey.equals(from));
+  assert.isDefined(key);
+  key.pubkey = to;
+  return ix;
+};
+
+describe("account constraints", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const claimant = Keypair.generate();
+  const amount = new anchor.BN(100);
+  const tree = new BalanceTree([{ account: claimant.publicKey, amount }]);
+
+  let distributorW: MerkleDistributorWrapper;
+  let otherMint: PublicKey;
+
+  const claimIx = () =>
+    distributorW.claimIX({
+      index: new anchor.BN(0),
+      amount,
+      proof: tree.getProof(0, claimant.publicKey, amount),
+      claimant: claimant.publicKey,
+      signers: [claimant],
+    });
+
+  const assertFails = async (
+    ix: TransactionInstruction,
+    signers: Keypair[],
+    msg: string
+  ) => {
+    try {
+      await processTransaction(provider, new Transaction().add(ix), signers);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, msg);
+    }
+  };
+
+  before(async () => {
+    await provider.connection.requestAirdrop(
+      claimant.publicKey,
+      LAMPORTS_PER_SOL
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+
+    await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      distributorW.data.mint,
+      claimant.publicKey
+    );
+
+    otherMint = await createMint(
+      provider.connection,
+      payer,
+      provider.publicKey,
+      provider.publicKey,
+      DEFAULT_TOKEN_DECIMALS
+    );
+  });
+
+  it("rejects a vault owned by someone else", async () => {
+    const vault = await createAccount(
+      provider.connection,
+      payer,
+      distributorW.data.mint,
+      claimant.publicKey,
+      Keypair.generate()
+    );
+
+    await assertFails(
+      spoof(claimIx(), distributorW.distributorATA, vault),
+      [claimant],
+      "A token owner constraint was violated"
+    );
+  });
+
+  it("rejects a distributor token account that is not its ATA", async () => {
+    const vault = await createAccount(
+      provider.connection,
+      payer,
+      distributorW.data.mint,
+      distributorW.key,
+      Keypair.generate()
+    );
+
+    await assertFails(
+      spoof(claimIx(), distributorW.distributorATA, vault),
+      [claimant],
+      "An associated constraint was violated"
+    );
+  });
+
+  it("rejects a destination of another mint", async () => {
+    const to = await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      otherMint,
+      claimant.publicKey
+    );
+
+    await assertFails(
+      spoof(
+        claimIx(),
+        getAssociatedTokenAddressSync(
+          distributorW.data.mint,
+          claimant.publicKey
+        ),
+        to
+      ),
+      [claimant],
+      "A token mint constraint was violated"
+    );
+  });
+
+  it("rejects a pushed destination of another mint", async () => {
+    const other = Keypair.generate();
+    const otherTree = new BalanceTree([{ account: other.publicKey, amount }]);
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      otherTree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    const otherW = await sdk.loadDistributor(distributorKey);
+    const to = await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      otherMint,
+      other.publicKey
+    );
+
+    const multiProof = otherTree.getMultiProof([
+      { index: 0, account: other.publicKey, amount },
+    ]);
+    const ix = otherW.distributeBatchIx({
+      entries: [{ claimant: other.publicKey, index: new anchor.BN(0), amount }],
+      proof: multiProof.proof,
+      proofFlags: multiProof.proofFlags,
+      adminAuth: payer,
+    });
+
+    await assertFails(
+      spoof(
+        ix,
+        getAssociatedTokenAddressSync(otherW.data.mint, other.publicKey),
+        to
+      ),
+      [payer],
+      "Token account mint did not match the distributor mint"
+    );
+  });
+
+  it("admin cannot sweep into an account of another mint", async () => {
+    const to = await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      otherMint,
+      payer.publicKey
+    );
+
+    const ix = sdk.program.instruction.adminClaimAfterExpiry({
+      accounts: {
+        distributor: distributorW.key,
+        from: distributorW.distributorATA,
+        to,
+        adminAuth: payer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    });
+
+    await assertFails(ix, [payer], "A token mint constraint was violated");
+  });
+
+  it("lets a third party pay for a claim the claimant signed", async () => {
+    const ix = claimIx();
+    // The claimant is listed as `claimant`, then as `payer`.
+    const claimantKeys = ix.keys.filter((k) =>
+      k.pubkey.equals(claimant.publicKey)
+    );
+    claimantKeys[1].pubkey = payer.publicKey;
+
+    await processTransaction(provider, new Transaction().add(ix), [
+      claimant,
+      payer,
+    ]);
+
+    const to = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      claimant.publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, to)).amount.toString(),
+      amount.toString()
+    );
+  });
+});
@@ -0,0 +1,126 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import { BalanceTree } from "../src/utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(2);
+const MAX_TOTAL_CLAIM = new anchor.BN(100 + 200);
+
+describe("claim delegate", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+
+  const kps = [Keypair.generate(), Keypair.generate()];
+  const amounts = [new anchor.BN(100), new anchor.BN(200)];
+  const tree = new BalanceTree(
+    kps.map((kp, i) => ({ account: kp.publicKey, amount: amounts[i] }))
+  );
+  const delegate = Keypair.generate();
+  const destination = Keypair.generate().publicKey;
+
+  const claimAsDelegate = (i: number, signer: Keypair = delegate) =>
+    distributorW.claimAsDelegate({
+      index: new anchor.BN(i),
+      amount: amounts[i],
+      proof: tree.getProof(i, kps[i].publicKey, amounts[i]),
+      claimant: kps[i].publicKey,
+      delegate: signer,
+    });
+
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    await Promise.all(
+      [...kps, delegate].map((kp) =>
+        provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL)
+      )
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+  });
+
+  it("claimant registers a delegate", async () => {
+    const signature = await sdk.setClaimDelegate({
+      claimant: kps[0],
+      delegate: delegate.publicKey,
+    });
+
+    const claimDelegate = await sdk.getClaimDelegate(kps[0].publicKey);
+    assert.isTrue(claimDelegate.delegate.equals(delegate.publicKey));
+    assert.isTrue(claimDelegate.destination.equals(kps[0].publicKey));
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ClaimDelegateSetEvent");
+  });
+
+  it("delegate claims to the claimant", async () => {
+    await claimAsDelegate(0);
+
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      kps[0].publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(100)
+    );
+    const claimStatus = await distributorW.getClaimStatus(kps[0].publicKey);
+    assert.isTrue(claimStatus.claimant.equals(kps[0].publicKey));
+    assert.isTrue(claimStatus.payer.equals(delegate.publicKey));
+  });
+
+  it("delegate claims to an approved destination", async () => {
+    await sdk.setClaimDelegate({
+      claimant: kps[1],
+      delegate: delegate.publicKey,
+      destination,
+    });
+
+    await claimAsDelegate(1);
+
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      destination
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(200)
+    );
+  });
+
+  it("other wallets cannot claim as the delegate", async () => {
+    try {
+      await claimAsDelegate(0, Keypair.generate());
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(
+        e.msg,
+        "Account is not authorized to execute this instruction"
+      );
+    }
+  });
+
+  it("claimant removes the delegate", async () => {
+    const signature = await sdk.removeClaimDelegate(kps[0]);
+
+    assert.isNull(await sdk.getClaimDelegate(kps[0].publicKey));
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ClaimDelegateRemovedEvent");
+  });
+});
@@ -0,0 +1,150 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import { BalanceTree } from "../src/utils";
+import { makeSDK, createAndSeedDistributor } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(2);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000_000_000_000);
+
+describe("claim many", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+
+  const claimant = Keypair.generate();
+  const other = Keypair.generate();
+
+  const treeA = new BalanceTree([
+    { account: claimant.publicKey, amount: new anchor.BN(100) },
+    { account: other.publicKey, amount: new anchor.BN(200) },
+  ]);
+  const treeB = new BalanceTree([
+    { account: other.publicKey, amount: new anchor.BN(300) },
+    { account: claimant.publicKey, amount: new anchor.BN(400) },
+  ]);
+
+  let distributorA: MerkleDistributorWrapper;
+  let distributorB: MerkleDistributorWrapper;
+
+  before(async () => {
+    await provider.connection.requestAirdrop(
+      claimant.publicKey,
+      LAMPORTS_PER_SOL
+    );
+
+    const { mint, distributorKey: keyA } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      treeA.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    const { distributorKey: keyB } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      treeB.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0),
+      mint
+    );
+
+    distributorA = await sdk.loadDistributor(keyA);
+    distributorB = await sdk.loadDistributor(keyB);
+  });
+
+  it("fails if any proof is invalid", async () => {
+    try {
+      await sdk.claimMany({
+        entries: [
+          {
+            distributor: distributorA,
+            index: new anchor.BN(0),
+            amount: new anchor.BN(100),
+            proof: treeA.getProof(0, claimant.publicKey, new anchor.BN(100)),
+          },
+          {
+            distributor: distributorB,
+            index: new anchor.BN(1),
+            amount: new anchor.BN(500),
+            proof: treeB.getProof(1, claimant.publicKey, new anchor.BN(400)),
+          },
+        ],
+        claimant: claimant.publicKey,
+        signers: [claimant],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Invalid Merkle proof.");
+    }
+  });
+
+  it("claims from two distributors at once", async () => {
+    await sdk.claimMany({
+      entries: [
+        {
+          distributor: distributorA,
+          index: new anchor.BN(0),
+          amount: new anchor.BN(100),
+          proof: treeA.getProof(0, claimant.publicKey, new anchor.BN(100)),
+        },
+        {
+          distributor: distributorB,
+          index: new anchor.BN(1),
+          amount: new anchor.BN(400),
+          proof: treeB.getProof(1, claimant.publicKey, new anchor.BN(400)),
+        },
+      ],
+      claimant: claimant.publicKey,
+      signers: [claimant],
+    });
+
+    const tokenAccount = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(distributorA.data.mint, claimant.publicKey)
+    );
+    assert.equal(tokenAccount.amount.toString(), "500");
+
+    const statusA = await distributorA.getClaimStatus(claimant.publicKey);
+    assert.equal(statusA.claimedAmount.toNumber(), 100);
+    const statusB = await distributorB.getClaimStatus(claimant.publicKey);
+    assert.equal(statusB.claimedAmount.toNumber(), 400);
+
+    await distributorA.reload();
+    await distributorB.reload();
+    assert.equal(distributorA.data.totalAmountClaimed.toNumber(), 100);
+    assert.equal(distributorB.data.totalAmountClaimed.toNumber(), 400);
+  });
+
+  it("cannot claim the same leaves twice", async () => {
+    try {
+      await sdk.claimMany({
+        entries: [
+          {
+            distributor: distributorA,
+            index: new anchor.BN(0),
+            amount: new anchor.BN(100),
+            proof: treeA.getProof(0, claimant.publicKey, new anchor.BN(100)),
+          },
+        ],
+        claimant: claimant.publicKey,
+        signers: [claimant],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "no claimable amount");
+    }
+  });
+});
@@ -0,0 +1,124 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import { BalanceTree } from "../src/utils";
+import { makeSDK, createAndSeedDistributor } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(3);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000_000_000_000);
+
+describe("distribute batch", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const adminAuth = (provider.wallet as anchor.Wallet).payer;
+
+  const kps = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const amounts = [new anchor.BN(100), new anchor.BN(101), new anchor.BN(102)];
+  const tree = new BalanceTree(
+    kps.map((kp, i) => ({ account: kp.publicKey, amount: amounts[i] }))
+  );
+  const multiProof = tree.getMultiProof(
+    kps.map((kp, i) => ({
+      index: i,
+      account: kp.publicKey,
+      amount: amounts[i],
+    }))
+  );
+  const entries = multiProof.leaves.map((leaf) => ({
+    claimant: leaf.account,
+    index: new anchor.BN(leaf.index),
+    amount: leaf.amount,
+  }));
+
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+  });
+
+  it("only the admin can push", async () => {
+    try {
+      await distributorW.distributeBatch({
+        entries,
+        proof: multiProof.proof,
+        proofFlags: multiProof.proofFlags,
+        adminAuth: kps[0],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Admin account not match distributor creator");
+    }
+  });
+
+  it("rejects entries out of tree order", async () => {
+    try {
+      await distributorW.distributeBatch({
+        entries: [...entries].reverse(),
+        proof: multiProof.proof,
+        proofFlags: multiProof.proofFlags,
+        adminAuth,
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Invalid Merkle proof.");
+    }
+  });
+
+  it("pushes tokens to every claimant", async () => {
+    await distributorW.distributeBatch({
+      entries,
+      proof: multiProof.proof,
+      proofFlags: multiProof.proofFlags,
+      adminAuth,
+    });
+
+    for (const [i, kp] of kps.entries()) {
+      const tokenAccount = await getAccount(
+        provider.connection,
+        getAssociatedTokenAddressSync(distributorW.data.mint, kp.publicKey)
+      );
+      assert.equal(tokenAccount.amount.toString(), amounts[i].toString());
+
+      const claimStatus = await distributorW.getClaimStatus(kp.publicKey);
+      assert.equal(claimStatus.claimedAmount.toString(), amounts[i].toString());
+      assert.equal(claimStatus.index.toNumber(), i);
+      assert.deepEqual(claimStatus.root, distributorW.data.root);
+      assert.equal(claimStatus.numClaims.toNumber(), 1);
+      assert.equal(claimStatus.payer.toBase58(), provider.publicKey.toBase58());
+    }
+
+    await distributorW.reload();
+    assert.equal(distributorW.data.numNodesClaimed.toNumber(), 3);
+    assert.equal(distributorW.data.totalAmountClaimed.toNumber(), 303);
+  });
+
+  it("claimants cannot claim pushed leaves again", async () => {
+    try {
+      await distributorW.claim({
+        index: new anchor.BN(0),
+        amount: amounts[0],
+        proof: tree.getProof(0, kps[0].publicKey, amounts[0]),
+        claimant: kps[0].publicKey,
+        signers: [kps[0]],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "no claimable amount");
+    }
+  });
+});
@@ -4,7 +4,7 @@ import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
 
 import { BalanceTree } from "../src/utils";
-import { makeSDK, createAndSeedDistributor } from "./utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
 import { MerkleDistributorWrapper, findDistributorKey } from "../src";
 import { sleepUntil } from "../deps/zeta-staking/tests/test-utils";
 
@@ -124,13 +124,13 @@ describe("distributor-early-and-late-claim", () => {
 
     const proof = tree.getProof(0, kpOne.publicKey, claimAmountOne);
 
-    await distributorW.claim({
+    const signature = (await distributorW.claim({
       index: new anchor.BN(0),
       amount: claimAmountOne,
       proof,
       claimant: kpOne.publicKey,
       signers: [kpOne],
-    });
+    })) as string;
 
     const slot = await provider.connection.getSlot({ commitment: "processed" });
     const timestamp = await provider.connection.getBlockTime(slot);
@@ -162,6 +162,27 @@ describe("distributor-early-and-late-claim", () => {
       claimStatus.claimedAmount.toString(),
       claimAmountOne.toString()
     );
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ClaimedEvent");
+    assert.equal(
+      event.data.distributor.toString(),
+      distributorW.key.toString()
+    );
+    assert.equal(event.data.claimAmount.toNumber(), claimAmountOne.toNumber());
+    assert.equal(event.data.transferAmount.toNumber(), actualClaimAmountOne);
+    assert.equal(
+      event.data.forfeitedAmount.toNumber(),
+      claimAmountOne.toNumber() - actualClaimAmountOne
+    );
+    assert.equal(
+      event.data.destination.toString(),
+      getAssociatedTokenAddressSync(
+        distributorW.data.mint,
+        kpOne.publicKey
+      ).toString()
+    );
+    assert.isFalse(event.data.staked);
   });
 
   it("try and claim again with user one after haircut over.", async () => {
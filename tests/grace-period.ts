@@ -0,0 +1,142 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import { BalanceTree } from "../src/utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+import { sleepUntil } from "../deps/zeta-staking/tests/test-utils";
+
+const MAX_NUM_NODES = new anchor.BN(2);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000 + 2_000);
+const GRACE_PERIOD_SECONDS = 6;
+const GRACE_CLAIM_PERCENTAGE = new anchor.BN(40_000000);
+
+describe("grace period", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const claimEndTs = Math.round(Date.now() / 1000) + 12;
+
+  const kpOne = Keypair.generate();
+  const kpTwo = Keypair.generate();
+  const amountOne = new anchor.BN(1_000);
+  const amountTwo = new anchor.BN(2_000);
+  const tree = new BalanceTree([
+    { account: kpOne.publicKey, amount: amountOne },
+    { account: kpTwo.publicKey, amount: amountTwo },
+  ]);
+
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    await Promise.all(
+      [kpOne, kpTwo].map((kp) =>
+        provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL)
+      )
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(claimEndTs),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+  });
+
+  it("admin sets a grace period", async () => {
+    const signature = await distributorW.updateGracePeriod({
+      gracePeriodSeconds: new anchor.BN(GRACE_PERIOD_SECONDS),
+      graceClaimPercentage: GRACE_CLAIM_PERCENTAGE,
+      adminAuth: payer,
+    });
+    await distributorW.reload();
+
+    assert.equal(
+      distributorW.data.gracePeriodSeconds.toNumber(),
+      GRACE_PERIOD_SECONDS
+    );
+    assert.equal(
+      distributorW.getClaimDeadline(),
+      claimEndTs + GRACE_PERIOD_SECONDS
+    );
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "GracePeriodUpdatedEvent");
+    assert.equal(
+      event.data.graceClaimPercentage.toNumber(),
+      GRACE_CLAIM_PERCENTAGE.toNumber()
+    );
+  });
+
+  it("late claims get the grace percentage and block sweeping", async () => {
+    await sleepUntil(claimEndTs + 1);
+
+    await distributorW.claim({
+      index: new anchor.BN(0),
+      amount: amountOne,
+      proof: tree.getProof(0, kpOne.publicKey, amountOne),
+      claimant: kpOne.publicKey,
+      signers: [kpOne],
+    });
+
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      kpOne.publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(400)
+    );
+    assert.equal(
+      distributorW.getEstimatedClaimAmount(
+        amountOne.toNumber(),
+        claimEndTs + 1
+      ),
+      400
+    );
+
+    try {
+      await distributorW.adminClaimAfterExpiry(payer);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(
+        e.msg,
+        "Can only admin claim after the claim window is over"
+      );
+    }
+  });
+
+  it("claims close and sweeps open after the grace period", async () => {
+    await sleepUntil(claimEndTs + GRACE_PERIOD_SECONDS + 1);
+
+    try {
+      await distributorW.claim({
+        index: new anchor.BN(1),
+        amount: amountTwo,
+        proof: tree.getProof(1, kpTwo.publicKey, amountTwo),
+        claimant: kpTwo.publicKey,
+        signers: [kpTwo],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Outside the claim window");
+    }
+
+    await distributorW.adminClaimAfterExpiry(payer);
+    assert.equal(
+      (await getAccount(provider.connection, distributorW.distributorATA))
+        .amount,
+      BigInt(0)
+    );
+  });
+});
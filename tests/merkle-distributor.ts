@@ -1,14 +1,24 @@
 import * as anchor from "@coral-xyz/anchor";
 import { assert } from "chai";
 
-import { BalanceTree } from "../src/utils";
+import {
+  BalanceTree,
+  HASH_FUNCTION_SHA256,
+  HASH_SCHEME_LEGACY,
+  HASH_SCHEME_TAGGED,
+} from "../src/utils";
 import {
   makeSDK,
   createAndSeedDistributor,
   createKeypairWithSOL,
+  DEFAULT_TOKEN_DECIMALS,
 } from "./utils";
 import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 
 const MAX_NUM_NODES = new anchor.BN(3);
 const MAX_TOTAL_CLAIM = new anchor.BN(1_000_000_000_000);
@@ -48,6 +58,7 @@ describe("merkle distributor", () => {
       data.totalAmountClaimed.toString(),
       new anchor.BN(0).toString()
     );
+    assert.equal(data.version, 1);
 
     const tokenAccountInfo = await getAccount(
       provider.connection,
@@ -60,6 +71,41 @@ describe("merkle distributor", () => {
     );
   });
 
+  it("cannot migrate a distributor on the current layout", async () => {
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      ZERO_BYTES32,
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+
+    try {
+      await sdk.migrateDistributor(distributorKey, Keypair.generate());
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Admin account not match distributor creator");
+    }
+
+    try {
+      await sdk.migrateDistributor(
+        distributorKey,
+        (provider.wallet as anchor.Wallet).payer
+      );
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(
+        e.msg,
+        "Distributor is already on the current layout version"
+      );
+    }
+  });
+
   it("fails for empty proof", async () => {
     const base1 = Keypair.generate();
 
@@ -179,6 +225,186 @@ describe("merkle distributor", () => {
       data.totalAmountClaimed.toString(),
       expectedTotalClaimed.toString()
     );
+
+    try {
+      await sdk.migrateClaimStatus(distributorKey, kpOne.publicKey);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Claim status is already on the current layout");
+    }
+  });
+
+  it("claims against a domain separated tree", async () => {
+    const base = Keypair.generate();
+
+    const kpOne = await createKeypairWithSOL(provider);
+    const kpTwo = await createKeypairWithSOL(provider);
+    const amountOne = new anchor.BN(1_000);
+    const amountTwo = new anchor.BN(2_000);
+    const tree = new BalanceTree(
+      [
+        { account: kpOne.publicKey, amount: amountOne },
+        { account: kpTwo.publicKey, amount: amountTwo },
+      ],
+      HASH_SCHEME_TAGGED
+    );
+    const legacyTree = new BalanceTree([
+      { account: kpOne.publicKey, amount: amountOne },
+      { account: kpTwo.publicKey, amount: amountTwo },
+    ]);
+    assert.notDeepEqual(tree.getRoot(), legacyTree.getRoot());
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      base,
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0),
+      undefined,
+      undefined,
+      HASH_SCHEME_TAGGED
+    );
+    const distributorW = await sdk.loadDistributor(distributorKey);
+    assert.deepEqual(distributorW.data.hashScheme, HASH_SCHEME_TAGGED);
+
+    try {
+      await distributorW.claim({
+        index: new anchor.BN(1),
+        amount: amountTwo,
+        proof: legacyTree.getProof(1, kpTwo.publicKey, amountTwo),
+        claimant: kpTwo.publicKey,
+        signers: [kpTwo],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Invalid Merkle proof.");
+    }
+
+    await distributorW.claim({
+      index: new anchor.BN(0),
+      amount: amountOne,
+      proof: tree.getProof(0, kpOne.publicKey, amountOne),
+      claimant: kpOne.publicKey,
+      signers: [kpOne],
+    });
+    const tokenAccountInfo = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(distributorW.data.mint, kpOne.publicKey)
+    );
+    assert.equal(tokenAccountInfo.amount.toString(), amountOne.toString());
+  });
+
+  it("claims against a sha256 tree", async () => {
+    const base = Keypair.generate();
+
+    const userKP = await createKeypairWithSOL(provider);
+    const claimAmount = new anchor.BN(1_000);
+    const tree = new BalanceTree(
+      [
+        { account: userKP.publicKey, amount: claimAmount },
+        { account: Keypair.generate().publicKey, amount: claimAmount },
+      ],
+      HASH_SCHEME_TAGGED,
+      undefined,
+      HASH_FUNCTION_SHA256
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      base,
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0),
+      undefined,
+      undefined,
+      HASH_SCHEME_TAGGED,
+      tree.leafVersion,
+      HASH_FUNCTION_SHA256
+    );
+    const distributorW = await sdk.loadDistributor(distributorKey);
+    assert.deepEqual(distributorW.data.hashFunction, HASH_FUNCTION_SHA256);
+
+    await distributorW.claim({
+      index: new anchor.BN(0),
+      amount: claimAmount,
+      proof: tree.getProof(0, userKP.publicKey, claimAmount),
+      claimant: userKP.publicKey,
+      signers: [userKP],
+    });
+    const claimStatus = await distributorW.getClaimStatus(userKP.publicKey);
+    assert.equal(claimStatus.claimedAmount.toString(), claimAmount.toString());
+  });
+
+  it("cannot replay a bound tree against another distributor", async () => {
+    const baseBound = Keypair.generate();
+    const baseOther = Keypair.generate();
+    const userKP = await createKeypairWithSOL(provider);
+    const claimAmount = new anchor.BN(1_000);
+
+    const mint = await createMint(
+      provider.connection,
+      (provider.wallet as anchor.Wallet).payer,
+      provider.publicKey,
+      provider.publicKey,
+      DEFAULT_TOKEN_DECIMALS
+    );
+    const tree = new BalanceTree(
+      [{ account: userKP.publicKey, amount: claimAmount }],
+      HASH_SCHEME_LEGACY,
+      { base: baseBound.publicKey, mint }
+    );
+
+    const distributors = await Promise.all(
+      [baseBound, baseOther].map(async (base) => {
+        const { distributorKey } = await createAndSeedDistributor(
+          sdk,
+          MAX_TOTAL_CLAIM,
+          MAX_NUM_NODES,
+          tree.getRoot(),
+          base,
+          new anchor.BN(0),
+          new anchor.BN(1809635703),
+          false,
+          new anchor.BN(100_000000),
+          new anchor.BN(0),
+          mint,
+          undefined,
+          HASH_SCHEME_LEGACY,
+          tree.leafVersion
+        );
+        return sdk.loadDistributor(distributorKey);
+      })
+    );
+    const [boundW, otherW] = distributors;
+    assert.deepEqual(boundW.data.leafVersion, tree.leafVersion);
+
+    const claimArgs = {
+      index: new anchor.BN(0),
+      amount: claimAmount,
+      proof: tree.getProof(0, userKP.publicKey, claimAmount),
+      claimant: userKP.publicKey,
+      signers: [userKP],
+    };
+    try {
+      await otherW.claim(claimArgs);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Invalid Merkle proof.");
+    }
+
+    await boundW.claim(claimArgs);
+    const claimStatus = await boundW.getClaimStatus(userKP.publicKey);
+    assert.equal(claimStatus.claimedAmount.toString(), claimAmount.toString());
   });
 
   it("cannot claim more than proof", async () => {
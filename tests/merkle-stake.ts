@@ -1,9 +1,17 @@
 import * as anchor from "@coral-xyz/anchor";
-import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 import { assert } from "chai";
 
 import { BalanceTree } from "../src/utils";
 import { makeSDK, createAndSeedDistributor } from "./utils";
+import {
+  STAKE_CLAIM_SCALING_FULL,
+  STAKE_CLAIM_SCALING_SCALED_WITH_STAKE_BASE,
+} from "../src";
 
 import {
   testSetup,
@@ -35,15 +43,19 @@ describe("merkle stake", () => {
   const airdropUser0 = anchor.web3.Keypair.generate();
   const airdropUser1 = anchor.web3.Keypair.generate();
   const unclaimedUser = anchor.web3.Keypair.generate();
-  const allAirdropKps = [airdropUser0, airdropUser1];
+  const targetUser = anchor.web3.Keypair.generate();
+  const scaledUser = anchor.web3.Keypair.generate();
+  const allAirdropKps = [airdropUser0, airdropUser1, targetUser, scaledUser];
   let DISTRIBUTOR_KEY: anchor.web3.PublicKey;
   const claimAmount0 = new anchor.BN(400);
   const claimAmount1 = new anchor.BN(567);
   const unclaimedAmount = new anchor.BN(54211);
+  const targetClaimAmount = new anchor.BN(250);
   const tree = new BalanceTree([
     { account: airdropUser0.publicKey, amount: claimAmount0 },
     { account: airdropUser1.publicKey, amount: claimAmount1 },
     { account: unclaimedUser.publicKey, amount: unclaimedAmount },
+    { account: targetUser.publicKey, amount: targetClaimAmount },
   ]);
 
   before(async () => {
@@ -65,12 +77,15 @@ describe("merkle stake", () => {
       })
     );
 
-    let amountToMint = claimAmount0.add(claimAmount1).add(unclaimedAmount);
+    let amountToMint = claimAmount0
+      .add(claimAmount1)
+      .add(unclaimedAmount)
+      .add(targetClaimAmount);
 
     let { distributorKey } = await createAndSeedDistributor(
       merkleSdk,
       amountToMint,
-      new anchor.BN(3),
+      new anchor.BN(4),
       tree.getRoot(),
       merkleBase,
       new anchor.BN(0),
@@ -235,6 +250,197 @@ describe("merkle stake", () => {
     assert.equal(airdropU1StakeAccount.stakeState, "Locked");
   });
 
+  it("claim into an approved staking program", async () => {
+    const distributorW = await merkleSdk.loadDistributor(DISTRIBUTOR_KEY);
+    const distributorAdminKp = (
+      distributorW.sdk.provider.wallet as anchor.Wallet
+    ).payer;
+    const proof = tree.getProof(3, targetUser.publicKey, targetClaimAmount);
+
+    const targetStakeUser = await Client.load(
+      Network.LOCALNET,
+      provider.connection,
+      commitmentConfig("confirmed"),
+      new anchor.Wallet(targetUser)
+    );
+    await targetStakeUser.createAndSendTx([
+      await targetStakeUser.createStakeAccountManager(),
+    ]);
+
+    // The amount is overwritten on-chain with the transferred amount.
+    const stakeIx = await State.program.methods
+      .stake(0, LOCKUP_EPOCHS, new anchor.BN(0), "cpi_target")
+      .accounts({
+        protocolState: State.protocolStateAddress,
+        zetaTokenAccount: getAssociatedTokenAddressSync(
+          distributorW.data.mint,
+          targetUser.publicKey
+        ),
+        stakeAccountManager: targetStakeUser.stakeAccountManagerAddress,
+        stakeAccount: targetStakeUser.stakeAccountAddresses[0],
+        stakeVault: targetStakeUser.stakeVaultAddresses[0],
+        authority: targetUser.publicKey,
+        zetaMint: State.protocolState.zetaMint,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .instruction();
+    const claimArgs = {
+      index: new anchor.BN(3),
+      amount: targetClaimAmount,
+      proof,
+      claimant: targetUser.publicKey,
+      signers: [targetUser],
+    };
+
+    try {
+      await distributorW.claimIntoProgram(claimArgs, stakeIx);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.notEqual(e.message, "Should not succeed");
+    }
+
+    // u8 bit + u32 duration precede the u64 amount.
+    await distributorW.addClaimTarget({
+      targetProgram: State.program.programId,
+      ixDiscriminator: stakeIx.data.subarray(0, 8),
+      amountOffset: 8 + 1 + 4,
+      adminAuth: distributorAdminKp,
+    });
+
+    await distributorW.claimIntoProgram(claimArgs, stakeIx);
+    await targetStakeUser.fetchUserState();
+
+    const stakeAccount = targetStakeUser.stakeAccounts.get(0)!;
+    assert.equal(stakeAccount.name, "cpi_target");
+    assert.equal(stakeAccount.amountStillStaked, targetClaimAmount.toNumber());
+    assert.equal(stakeAccount.stakeDurationEpochs, LOCKUP_EPOCHS);
+
+    await distributorW.removeClaimTarget(
+      State.program.programId,
+      distributorAdminKp
+    );
+  });
+
+  it("update stake claim scaling", async () => {
+    const distributorW = await merkleSdk.loadDistributor(DISTRIBUTOR_KEY);
+    assert.deepEqual(
+      distributorW.data.stakeClaimScaling,
+      STAKE_CLAIM_SCALING_FULL
+    );
+    assert.equal(distributorW.getEstimatedClaimAmount(1000, 0, true), 1000);
+
+    const distributorAdminKp = (
+      distributorW.sdk.provider.wallet as anchor.Wallet
+    ).payer;
+    await distributorW.updateStakeClaimScaling({
+      stakeClaimScaling: STAKE_CLAIM_SCALING_SCALED_WITH_STAKE_BASE,
+      stakeImmediateClaimPercentage: new anchor.BN(80_000000),
+      adminAuth: distributorAdminKp,
+    });
+    await distributorW.reload();
+
+    assert.deepEqual(
+      distributorW.data.stakeClaimScaling,
+      STAKE_CLAIM_SCALING_SCALED_WITH_STAKE_BASE
+    );
+    assert.equal(
+      distributorW.data.stakeImmediateClaimPercentage.toNumber(),
+      80_000000
+    );
+
+    try {
+      await distributorW.updateStakeClaimScaling({
+        stakeClaimScaling: STAKE_CLAIM_SCALING_SCALED_WITH_STAKE_BASE,
+        stakeImmediateClaimPercentage: new anchor.BN(100_000001),
+        adminAuth: distributorAdminKp,
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.notEqual(e.message, "Should not succeed");
+    }
+  });
+
+  it("staked claims follow the ramp from the stake base", async () => {
+    const scaledAmount = new anchor.BN(1_000);
+    const scaledTree = new BalanceTree([
+      { account: scaledUser.publicKey, amount: scaledAmount },
+      {
+        account: anchor.web3.Keypair.generate().publicKey,
+        amount: scaledAmount,
+      },
+    ]);
+    const now = Math.round(Date.now() / 1000);
+    // A ramp long enough that the claim gets the base percentage.
+    const { distributorKey } = await createAndSeedDistributor(
+      merkleSdk,
+      scaledAmount.muln(2),
+      new anchor.BN(2),
+      scaledTree.getRoot(),
+      anchor.web3.Keypair.generate(),
+      new anchor.BN(now - 1),
+      new anchor.BN(now + 1_000_000_000),
+      false,
+      new anchor.BN(50_000000),
+      new anchor.BN(100_000_000),
+      getZetaMintKeypair().publicKey,
+      getAdminKeypair()
+    );
+    const distributorW = await merkleSdk.loadDistributor(distributorKey);
+    await distributorW.updateStakeClaimScaling({
+      stakeClaimScaling: STAKE_CLAIM_SCALING_SCALED_WITH_STAKE_BASE,
+      stakeImmediateClaimPercentage: new anchor.BN(80_000000),
+      adminAuth: (distributorW.sdk.provider.wallet as anchor.Wallet).payer,
+    });
+
+    const scaledStakeUser = await Client.load(
+      Network.LOCALNET,
+      provider.connection,
+      commitmentConfig("confirmed"),
+      new anchor.Wallet(scaledUser)
+    );
+    await scaledStakeUser.createAndSendTx([
+      await scaledStakeUser.createStakeAccountManager(),
+    ]);
+
+    await distributorW.claimStake(
+      {
+        index: new anchor.BN(0),
+        amount: scaledAmount,
+        proof: scaledTree.getProof(0, scaledUser.publicKey, scaledAmount),
+        claimant: scaledUser.publicKey,
+        signers: [scaledUser],
+      },
+      {
+        zetaStaking: State.program.programId,
+        protocolState: State.protocolStateAddress,
+        stakeAccountManager: scaledStakeUser.stakeAccountManagerAddress,
+        stakeAccount: scaledStakeUser.stakeAccountAddresses[0],
+        stakeVault: scaledStakeUser.stakeVaultAddresses[0],
+        zetaMint: State.protocolState.zetaMint,
+      },
+      0,
+      "cpi_scaled",
+      LOCKUP_EPOCHS
+    );
+    await scaledStakeUser.fetchUserState();
+
+    // 80% from the stake base rather than the 50% of direct claims.
+    const stakeAccount = scaledStakeUser.stakeAccounts.get(0)!;
+    assert.equal(stakeAccount.initialStakeAmount, 800);
+    assert.equal(stakeAccount.amountStillStaked, 800);
+
+    await distributorW.reload();
+    assert.equal(distributorW.data.totalAmountClaimed.toNumber(), 1_000);
+    assert.equal(
+      Number(
+        (await getAccount(provider.connection, distributorW.distributorATA))
+          .amount
+      ),
+      1_200
+    );
+  });
+
   it("claim back unclaimed airdrop after expiry", async () => {
     const distributorW = await merkleSdk.loadDistributor(DISTRIBUTOR_KEY);
 
@@ -0,0 +1,179 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  NATIVE_MINT,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+
+import { BalanceTree, processTransaction } from "../src/utils";
+import { makeSDK, getEvents } from "./utils";
+import { MerkleDistributorWrapper, findClaimStatusKey } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(3);
+const MAX_TOTAL_CLAIM = new anchor.BN(LAMPORTS_PER_SOL / 10);
+const EXCESS = new anchor.BN(LAMPORTS_PER_SOL / 100);
+
+describe("native distribution", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const claimant = Keypair.generate();
+  const revoked = Keypair.generate().publicKey;
+  const rebound = Keypair.generate();
+  const amount = MAX_TOTAL_CLAIM.divn(5);
+  const tree = new BalanceTree([
+    { account: claimant.publicKey, amount },
+    { account: revoked, amount: amount.muln(3) },
+    { account: rebound.publicKey, amount },
+  ]);
+
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    await Promise.all(
+      [claimant, rebound].map((kp) =>
+        provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL)
+      )
+    );
+
+    const { distributor } = await sdk.createDistributor({
+      root: tree.getRoot(),
+      maxTotalClaim: MAX_TOTAL_CLAIM,
+      maxNumNodes: MAX_NUM_NODES,
+      tokenMint: NATIVE_MINT,
+      adminAuth: payer,
+      base: Keypair.generate(),
+      claimStartTs: new anchor.BN(0),
+      claimEndTs: new anchor.BN(1809635703),
+      stakeClaimOnly: false,
+      immediateClaimPercentage: new anchor.BN(100_000000),
+      laterClaimOffsetSeconds: new anchor.BN(0),
+      native: true,
+    });
+
+    // Seed the distributor with lamports, some beyond what claimants are owed.
+    await processTransaction(
+      provider,
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: payer.publicKey,
+          toPubkey: distributor,
+          lamports: MAX_TOTAL_CLAIM.add(EXCESS).toNumber(),
+        })
+      )
+    );
+    distributorW = await sdk.loadDistributor(distributor);
+  });
+
+  it("claims lamports into the claimant's wallet", async () => {
+    assert.isTrue(distributorW.data.native);
+    const before = await provider.connection.getBalance(claimant.publicKey);
+
+    const signature = (await distributorW.claimNative({
+      index: new anchor.BN(0),
+      amount,
+      proof: tree.getProof(0, claimant.publicKey, amount),
+      claimant: claimant.publicKey,
+      signers: [claimant],
+    })) as string;
+
+    // The claimant also paid the rent of its claim status.
+    const claimStatusRent = await provider.connection.getBalance(
+      findClaimStatusKey(claimant.publicKey, distributorW.key)[0]
+    );
+    assert.equal(
+      (await provider.connection.getBalance(claimant.publicKey)) - before,
+      amount.toNumber() - claimStatusRent
+    );
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ClaimedEvent");
+    assert.isTrue(event.data.destination.equals(claimant.publicKey));
+  });
+
+  it("a rebound leaf is claimed into the new wallet", async () => {
+    const newWallet = Keypair.generate();
+    const leaf = {
+      index: new anchor.BN(2),
+      amount,
+      proof: tree.getProof(2, rebound.publicKey, amount),
+    };
+    await distributorW.rebindClaim({
+      ...leaf,
+      claimant: rebound,
+      newClaimant: newWallet.publicKey,
+    });
+
+    // The rebound leaf's claim status was paid for when rebinding.
+    await distributorW.claimNative({
+      ...leaf,
+      claimant: newWallet.publicKey,
+      signers: [newWallet],
+      rebound: rebound.publicKey,
+    });
+    assert.equal(
+      await provider.connection.getBalance(newWallet.publicKey),
+      amount.toNumber()
+    );
+  });
+
+  it("admin cannot sweep lamports still owed to claimants", async () => {
+    try {
+      await distributorW.adminSweepNative(payer, EXCESS.addn(1));
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Amount exceeds what can be swept");
+    }
+  });
+
+  it("admin sweeps the excess lamports", async () => {
+    await distributorW.adminSweepNative(payer, EXCESS);
+
+    assert.equal(
+      (await distributorW.getNativeVaultAmount()).toNumber(),
+      MAX_TOTAL_CLAIM.sub(amount.muln(2)).toNumber()
+    );
+  });
+
+  it("cannot set a sweep recipient", async () => {
+    const { address } = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      NATIVE_MINT,
+      payer.publicKey
+    );
+    try {
+      await distributorW.setSweepRecipient(payer, address);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(
+        e.msg,
+        "Native distributors are swept with admin_sweep_native"
+      );
+    }
+  });
+
+  it("admin revokes a leaf and sweeps its lamports", async () => {
+    await distributorW.revokeClaim({
+      index: new anchor.BN(1),
+      amount: amount.muln(3),
+      proof: tree.getProof(1, revoked, amount.muln(3)),
+      claimant: revoked,
+      adminAuth: payer,
+    });
+
+    const claimStatus = await distributorW.getClaimStatus(revoked);
+    assert.isFalse(claimStatus.revokedAt.isZero());
+    // Nothing is owed to claimants anymore.
+    await distributorW.reload();
+    await distributorW.adminSweepNative(payer, amount.muln(3));
+    assert.equal((await distributorW.getNativeVaultAmount()).toNumber(), 0);
+  });
+});
@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createAccount, getAccount, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+import { BalanceTree, toBytes32Array } from "../src/utils";
+import { makeSDK, createAndSeedDistributor } from "./utils";
+import { MerkleDistributorWrapper, findClaimStatusKey } from "../src";
+import { ExamplePdaClaimer } from "../target/types/example_pda_claimer";
+
+const MAX_NUM_NODES = new anchor.BN(2);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000 + 2_000);
+
+describe("pda claim", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const example = anchor.workspace
+    .ExamplePdaClaimer as anchor.Program<ExamplePdaClaimer>;
+
+  // A vault PDA of the example program, standing in for a DAO treasury.
+  const [vault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), payer.publicKey.toBuffer()],
+    example.programId
+  );
+  const amount = new anchor.BN(1_000);
+  const tree = new BalanceTree([
+    { account: vault, amount },
+    { account: Keypair.generate().publicKey, amount: new anchor.BN(2_000) },
+  ]);
+
+  let distributorW: MerkleDistributorWrapper;
+  let vaultTokens: PublicKey;
+
+  before(async () => {
+    const { mint, distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+
+    vaultTokens = await createAccount(
+      provider.connection,
+      payer,
+      mint,
+      vault,
+      Keypair.generate()
+    );
+  });
+
+  it("a PDA claims through a CPI signed by its program", async () => {
+    await example.methods
+      .claim(
+        new anchor.BN(0),
+        amount,
+        null,
+        tree.getProof(0, vault, amount).map((p) => toBytes32Array(p))
+      )
+      .accounts({
+        owner: payer.publicKey,
+        vault,
+        vaultTokens,
+        distributor: distributorW.key,
+        claimStatus: findClaimStatusKey(vault, distributorW.key)[0],
+        from: distributorW.distributorATA,
+        merkleDistributorProgram: sdk.program.programId,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        screeningFlag: null,
+      })
+      .rpc();
+
+    assert.equal(
+      (await getAccount(provider.connection, vaultTokens)).amount,
+      BigInt(1_000)
+    );
+    const claimStatus = await distributorW.getClaimStatus(vault);
+    assert.isTrue(claimStatus.claimant.equals(vault));
+    assert.isTrue(claimStatus.payer.equals(payer.publicKey));
+  });
+});
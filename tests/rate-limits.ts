@@ -0,0 +1,154 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import { BalanceTree } from "../src/utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(2);
+const MAX_TOTAL_CLAIM = new anchor.BN(100 + 200);
+
+describe("rate limits", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const kps = [Keypair.generate(), Keypair.generate()];
+  const amounts = [new anchor.BN(100), new anchor.BN(200)];
+  const tree = new BalanceTree(
+    kps.map((kp, i) => ({ account: kp.publicKey, amount: amounts[i] }))
+  );
+
+  const claim = (i: number) =>
+    distributorW.claim({
+      index: new anchor.BN(i),
+      amount: amounts[i],
+      proof: tree.getProof(i, kps[i].publicKey, amounts[i]),
+      claimant: kps[i].publicKey,
+      signers: [kps[i]],
+    }) as Promise<string>;
+
+  const balance = async (i: number): Promise<bigint> => {
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      kps[i].publicKey
+    );
+    try {
+      return (await getAccount(provider.connection, ata)).amount;
+    } catch (e) {
+      return BigInt(0);
+    }
+  };
+
+  const setLimits = (maxClaim: number, maxClaimantClaim: number) =>
+    distributorW.updateRateLimits({
+      // A single window for the whole test.
+      rateLimitWindowSeconds: new anchor.BN(1_000_000_000),
+      maxClaimPerWindow: new anchor.BN(maxClaim),
+      maxClaimantClaimPerWindow: new anchor.BN(maxClaimantClaim),
+      adminAuth: payer,
+    });
+
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    await Promise.all(
+      kps.map((kp) =>
+        provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL)
+      )
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+  });
+
+  it("claims within the limits go through", async () => {
+    await setLimits(250, 150);
+    await claim(0);
+
+    assert.equal(await balance(0), BigInt(100));
+    await distributorW.reload();
+    assert.equal(
+      distributorW.data.rateLimitWindow.claimedAmount.toNumber(),
+      100
+    );
+    const claimStatus = await distributorW.getClaimStatus(kps[0].publicKey);
+    assert.equal(claimStatus.rateLimitWindow.claimedAmount.toNumber(), 100);
+  });
+
+  it("caps a claim over the claimant limit", async () => {
+    const signature = await claim(1);
+
+    assert.equal(await balance(1), BigInt(150));
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ClaimedEvent");
+    assert.equal(event.data.transferAmount.toNumber(), 150);
+    const claimStatus = await distributorW.getClaimStatus(kps[1].publicKey);
+    assert.equal(claimStatus.claimedAmount.toNumber(), 150);
+  });
+
+  it("fails a claim once the claimant limit is used up", async () => {
+    try {
+      await claim(1);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Claim exceeds the rate limits of the distributor");
+    }
+  });
+
+  it("fails a claim once the global limit is used up", async () => {
+    await setLimits(250, 0);
+    try {
+      await claim(1);
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Claim exceeds the rate limits of the distributor");
+    }
+  });
+
+  it("skips a claim over the limit in a batch", async () => {
+    const multiProof = tree.getMultiProof([
+      { index: 1, account: kps[1].publicKey, amount: amounts[1] },
+    ]);
+    const signature = await distributorW.distributeBatch({
+      entries: [
+        {
+          claimant: kps[1].publicKey,
+          index: new anchor.BN(1),
+          amount: amounts[1],
+        },
+      ],
+      proof: multiProof.proof,
+      proofFlags: multiProof.proofFlags,
+      adminAuth: payer,
+    });
+
+    assert.equal(await balance(1), BigInt(150));
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "RateLimitTrippedEvent");
+    assert.deepEqual(event.data.limit, { global: {} });
+    assert.equal(event.data.transferAmount.toNumber(), 50);
+  });
+
+  it("claims the rest once the limit is raised", async () => {
+    await setLimits(300, 0);
+    await claim(1);
+
+    assert.equal(await balance(1), BigInt(200));
+    await distributorW.reload();
+    assert.equal(distributorW.data.totalAmountClaimed.toNumber(), 300);
+  });
+});
@@ -0,0 +1,232 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import { BalanceTree } from "../src/utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(4);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000 + 2_000 + 3_000 + 4_000);
+
+describe("rebind claim", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+
+  const kps = [...Array(4)].map(() => Keypair.generate());
+  const amounts = [
+    new anchor.BN(1_000),
+    new anchor.BN(2_000),
+    new anchor.BN(3_000),
+    new anchor.BN(4_000),
+  ];
+  const tree = new BalanceTree(
+    kps.map((kp, i) => ({ account: kp.publicKey, amount: amounts[i] }))
+  );
+  const newWallet = Keypair.generate();
+
+  const leaf = {
+    index: new anchor.BN(0),
+    amount: amounts[0],
+    proof: tree.getProof(0, kps[0].publicKey, amounts[0]),
+  };
+
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    await Promise.all(
+      [...kps, newWallet].map((kp) =>
+        provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL)
+      )
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+  });
+
+  it("claimant rebinds its leaf to a new wallet", async () => {
+    const signature = await distributorW.rebindClaim({
+      ...leaf,
+      claimant: kps[0],
+      newClaimant: newWallet.publicKey,
+    });
+
+    const claimStatus = await distributorW.getClaimStatus(kps[0].publicKey);
+    assert.isTrue(claimStatus.reboundTo.equals(newWallet.publicKey));
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ClaimReboundEvent");
+    assert.isTrue(event.data.newClaimant.equals(newWallet.publicKey));
+  });
+
+  it("the original claimant can no longer claim", async () => {
+    try {
+      await distributorW.claim({
+        ...leaf,
+        claimant: kps[0].publicKey,
+        signers: [kps[0]],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Claim was rebound to another wallet");
+    }
+  });
+
+  it("other claim instructions cannot claim a rebound leaf", async () => {
+    const expectRebound = async (tx: Promise<unknown>) => {
+      try {
+        await tx;
+        throw Error("Should not succeed");
+      } catch (e) {
+        assert.equal(e.msg, "Claim was rebound to another wallet");
+      }
+    };
+
+    await expectRebound(
+      sdk.claimMany({
+        entries: [{ distributor: distributorW, ...leaf }],
+        claimant: kps[0].publicKey,
+        signers: [kps[0]],
+      })
+    );
+
+    // One rebound entry reverts the whole batch.
+    const multiProof = tree.getMultiProof([
+      { index: 0, account: kps[0].publicKey, amount: amounts[0] },
+    ]);
+    await expectRebound(
+      distributorW.distributeBatch({
+        entries: [
+          {
+            claimant: kps[0].publicKey,
+            index: leaf.index,
+            amount: leaf.amount,
+          },
+        ],
+        proof: multiProof.proof,
+        proofFlags: multiProof.proofFlags,
+        adminAuth: (provider.wallet as anchor.Wallet).payer,
+      })
+    );
+  });
+
+  it("the new wallet claims the leaf", async () => {
+    await distributorW.claim({
+      ...leaf,
+      claimant: newWallet.publicKey,
+      signers: [newWallet],
+      rebound: kps[0].publicKey,
+    });
+
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      newWallet.publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(1_000)
+    );
+    const claimStatus = await distributorW.getReboundClaimStatus(
+      kps[0].publicKey
+    );
+    assert.isTrue(claimStatus.claimant.equals(kps[0].publicKey));
+    assert.equal(claimStatus.claimedAmount.toNumber(), 1_000);
+  });
+
+  it("a wallet with a leaf of its own claims both", async () => {
+    const own = {
+      index: new anchor.BN(1),
+      amount: amounts[1],
+      proof: tree.getProof(1, kps[1].publicKey, amounts[1]),
+    };
+    const rebound = {
+      index: new anchor.BN(3),
+      amount: amounts[3],
+      proof: tree.getProof(3, kps[3].publicKey, amounts[3]),
+    };
+    await distributorW.rebindClaim({
+      ...rebound,
+      claimant: kps[3],
+      newClaimant: kps[1].publicKey,
+    });
+
+    await distributorW.claim({
+      ...own,
+      claimant: kps[1].publicKey,
+      signers: [kps[1]],
+    });
+    await distributorW.claim({
+      ...rebound,
+      claimant: kps[1].publicKey,
+      signers: [kps[1]],
+      rebound: kps[3].publicKey,
+    });
+
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      kps[1].publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(2_000 + 4_000)
+    );
+    const claimStatus = await distributorW.getClaimStatus(kps[1].publicKey);
+    assert.isTrue(claimStatus.claimant.equals(kps[1].publicKey));
+    assert.equal(claimStatus.claimedAmount.toNumber(), 2_000);
+  });
+
+  it("revoking a rebound leaf revokes the new wallet's claim", async () => {
+    const revoked = {
+      index: new anchor.BN(2),
+      amount: amounts[2],
+      proof: tree.getProof(2, kps[2].publicKey, amounts[2]),
+    };
+    const otherWallet = Keypair.generate();
+    await provider.connection.requestAirdrop(
+      otherWallet.publicKey,
+      LAMPORTS_PER_SOL
+    );
+    await distributorW.rebindClaim({
+      ...revoked,
+      claimant: kps[2],
+      newClaimant: otherWallet.publicKey,
+    });
+
+    await distributorW.revokeClaim({
+      ...revoked,
+      claimant: kps[2].publicKey,
+      adminAuth: (provider.wallet as anchor.Wallet).payer,
+    });
+
+    const claimStatus = await distributorW.getReboundClaimStatus(
+      kps[2].publicKey
+    );
+    assert.isFalse(claimStatus.revokedAt.isZero());
+    try {
+      await distributorW.claim({
+        ...revoked,
+        claimant: otherWallet.publicKey,
+        signers: [otherWallet],
+        rebound: kps[2].publicKey,
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Claim was revoked by the admin");
+    }
+
+    await distributorW.reload();
+    assert.equal(distributorW.data.maxTotalClaim.toNumber(), 7_000);
+  });
+});
@@ -0,0 +1,157 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  createAssociatedTokenAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import { BalanceTree } from "../src/utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(3);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000 + 2_000 + 3_000);
+
+describe("revoke claim", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const kps = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const amounts = [1_000, 2_000, 3_000].map((a) => new anchor.BN(a));
+  const tree = new BalanceTree(
+    kps.map((kp, i) => ({ account: kp.publicKey, amount: amounts[i] }))
+  );
+
+  const leaf = (i: number) => ({
+    index: new anchor.BN(i),
+    amount: amounts[i],
+    proof: tree.getProof(i, kps[i].publicKey, amounts[i]),
+    claimant: kps[i].publicKey,
+  });
+
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    await Promise.all(
+      kps.map((kp) =>
+        provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL)
+      )
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+  });
+
+  it("admin revokes a claim and takes the allocation back", async () => {
+    const treasury = await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      distributorW.data.mint,
+      payer.publicKey
+    );
+
+    const signature = await distributorW.revokeClaim({
+      ...leaf(0),
+      adminAuth: payer,
+      returnTo: treasury,
+    });
+
+    assert.equal(
+      (await getAccount(provider.connection, treasury)).amount,
+      BigInt(1_000)
+    );
+    await distributorW.reload();
+    assert.equal(distributorW.data.maxTotalClaim.toNumber(), 5_000);
+    const claimStatus = await distributorW.getClaimStatus(kps[0].publicKey);
+    assert.isAbove(claimStatus.revokedAt.toNumber(), 0);
+    assert.equal(claimStatus.claimedAmount.toNumber(), 0);
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ClaimRevokedEvent");
+    assert.equal(event.data.revokedAmount.toNumber(), 1_000);
+    assert.isTrue(event.data.returned);
+  });
+
+  it("a revoked claimant cannot claim", async () => {
+    try {
+      await distributorW.claim({ ...leaf(0), signers: [kps[0]] });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Claim was revoked by the admin");
+    }
+  });
+
+  it("cannot revoke twice", async () => {
+    try {
+      await distributorW.revokeClaim({ ...leaf(0), adminAuth: payer });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Claim was revoked by the admin");
+    }
+  });
+
+  it("cannot revoke an allocation that was claimed", async () => {
+    await distributorW.claim({ ...leaf(1), signers: [kps[1]] });
+
+    try {
+      await distributorW.revokeClaim({ ...leaf(1), adminAuth: payer });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "no claimable amount");
+    }
+  });
+
+  it("only the admin can revoke", async () => {
+    try {
+      await distributorW.revokeClaim({ ...leaf(2), adminAuth: kps[2] });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Admin account not match distributor creator");
+    }
+  });
+
+  it("a revoked allocation kept in the vault becomes sweepable", async () => {
+    await distributorW.revokeClaim({ ...leaf(2), adminAuth: payer });
+    await distributorW.reload();
+
+    const vault = await getAccount(
+      provider.connection,
+      distributorW.distributorATA
+    );
+    assert.equal(vault.amount, BigInt(3_000));
+    assert.equal(
+      distributorW
+        .getSweepableAmount(new anchor.BN(3_000), Date.now() / 1000)
+        .toNumber(),
+      3_000
+    );
+
+    await distributorW.adminSweep(payer, new anchor.BN(3_000));
+    assert.equal(
+      (
+        await getAccount(
+          provider.connection,
+          getAssociatedTokenAddressSync(
+            distributorW.data.mint,
+            payer.publicKey
+          )
+        )
+      ).amount,
+      BigInt(1_000 + 3_000)
+    );
+  });
+});
@@ -0,0 +1,332 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  createAssociatedTokenAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+
+import {
+  BalanceTree,
+  processTransaction,
+  toBytes32Array,
+} from "../src/utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
+import {
+  MerkleDistributorWrapper,
+  findClaimRebindKey,
+  findClaimStatusKey,
+  findReboundClaimStatusKey,
+  findScreeningFlagKey,
+} from "../src";
+
+const MAX_NUM_NODES = new anchor.BN(3);
+const MAX_TOTAL_CLAIM = new anchor.BN(100 + 200 + 300);
+
+describe("screening", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  // Its only flag account, loaded from tests/fixtures/screening-flag.json,
+  // flags `flaggedWallet`; every other wallet passes screening.
+  const screeningProgram = new PublicKey(
+    "8mRqX9pjKu7YYUgFQ7oqiimFW1ZM72gbDnDbwbRopWwi"
+  );
+  const flaggedWallet = new PublicKey(
+    "7tpSNV82LGx6whWAhng4sF5UkbTBsm6KEEGbC1PAYaq6"
+  );
+
+  const claimant = Keypair.generate();
+  const other = Keypair.generate();
+  const delegator = Keypair.generate();
+  const amount = new anchor.BN(100);
+  const tree = new BalanceTree([
+    { account: claimant.publicKey, amount },
+    { account: other.publicKey, amount: new anchor.BN(200) },
+    { account: delegator.publicKey, amount: new anchor.BN(300) },
+  ]);
+  const claimArgs = {
+    index: new anchor.BN(0),
+    amount,
+    proof: tree.getProof(0, claimant.publicKey, amount),
+    claimant: claimant.publicKey,
+    signers: [claimant],
+  };
+
+  let distributorW: MerkleDistributorWrapper;
+
+  const claimWithFlag = (screeningFlag: PublicKey | null) =>
+    processTransaction(
+      provider,
+      new Transaction().add(
+        sdk.program.instruction.claim(
+          claimArgs.index,
+          amount,
+          null,
+          claimArgs.proof.map((p) => toBytes32Array(p)),
+          {
+            accounts: {
+              distributor: distributorW.key,
+              claimStatus: findClaimStatusKey(
+                claimant.publicKey,
+                distributorW.key
+              )[0],
+              from: distributorW.distributorATA,
+              to: getAssociatedTokenAddressSync(
+                distributorW.data.mint,
+                claimant.publicKey
+              ),
+              claimant: claimant.publicKey,
+              payer: claimant.publicKey,
+              systemProgram: SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              screeningFlag,
+            },
+          }
+        )
+      ),
+      [claimant]
+    );
+
+  before(async () => {
+    await Promise.all(
+      [claimant, other, delegator].map((kp) =>
+        provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL)
+      )
+    );
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(1809635703),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+
+    await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      distributorW.data.mint,
+      claimant.publicKey
+    );
+  });
+
+  it("admin sets a screening program", async () => {
+    const signature = await distributorW.updateScreeningProgram(
+      payer,
+      screeningProgram
+    );
+    await distributorW.reload();
+    assert.isTrue(distributorW.data.screeningProgram.equals(screeningProgram));
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "ScreeningProgramUpdatedEvent");
+    assert.isTrue(event.data.screeningProgram.equals(screeningProgram));
+  });
+
+  it("requires the claimant's screening flag account", async () => {
+    const [otherFlag] = findScreeningFlagKey(other.publicKey, screeningProgram);
+
+    for (const screeningFlag of [null, otherFlag]) {
+      try {
+        await claimWithFlag(screeningFlag);
+        throw Error("Should not succeed");
+      } catch (e) {
+        assert.equal(
+          e.msg,
+          "Screening flag account is missing or not the claimant's"
+        );
+      }
+    }
+  });
+
+  it("unflagged claimants can claim", async () => {
+    await distributorW.claim(claimArgs);
+
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      claimant.publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(100)
+    );
+  });
+
+  it("cannot claim many from a screened distributor", async () => {
+    try {
+      await sdk.claimMany({
+        entries: [
+          {
+            distributor: distributorW,
+            index: new anchor.BN(1),
+            amount: new anchor.BN(200),
+            proof: tree.getProof(1, other.publicKey, new anchor.BN(200)),
+          },
+        ],
+        claimant: other.publicKey,
+        signers: [other],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(
+        e.msg,
+        "Claims from a screened distributor must go through claim, claim_stake, claim_into_program, claim_as_delegate or claim_native"
+      );
+    }
+  });
+
+  it("screens the original claimant of a rebound leaf", async () => {
+    const newWallet = Keypair.generate();
+    await provider.connection.requestAirdrop(
+      newWallet.publicKey,
+      LAMPORTS_PER_SOL
+    );
+    const leaf = {
+      index: new anchor.BN(1),
+      amount: new anchor.BN(200),
+      proof: tree.getProof(1, other.publicKey, new anchor.BN(200)),
+    };
+    const expectMissingFlag = async (tx: Promise<string>) => {
+      try {
+        await tx;
+        throw Error("Should not succeed");
+      } catch (e) {
+        assert.equal(
+          e.msg,
+          "Screening flag account is missing or not the claimant's"
+        );
+      }
+    };
+
+    const rebindAccounts = {
+      distributor: distributorW.key,
+      claimStatus: findClaimStatusKey(other.publicKey, distributorW.key)[0],
+      reboundClaimStatus: findReboundClaimStatusKey(
+        other.publicKey,
+        distributorW.key
+      )[0],
+      claimRebind: findClaimRebindKey(distributorW.key, newWallet.publicKey)[0],
+      claimant: other.publicKey,
+      newClaimant: newWallet.publicKey,
+      payer: other.publicKey,
+      systemProgram: SystemProgram.programId,
+      screeningFlag: null,
+    };
+    await expectMissingFlag(
+      processTransaction(
+        provider,
+        new Transaction().add(
+          sdk.program.instruction.rebindClaim(
+            leaf.index,
+            leaf.amount,
+            null,
+            leaf.proof.map((p) => toBytes32Array(p)),
+            { accounts: rebindAccounts }
+          )
+        ),
+        [other]
+      )
+    );
+    await distributorW.rebindClaim({
+      ...leaf,
+      claimant: other,
+      newClaimant: newWallet.publicKey,
+    });
+
+    const claimArgs = {
+      ...leaf,
+      claimant: newWallet.publicKey,
+      signers: [newWallet],
+      rebound: other.publicKey,
+    };
+    await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      distributorW.data.mint,
+      newWallet.publicKey
+    );
+    const ix = distributorW.claimIX(claimArgs);
+    // Drop the original claimant's flag, the last account.
+    ix.keys[ix.keys.length - 1] = {
+      pubkey: sdk.program.programId,
+      isSigner: false,
+      isWritable: false,
+    };
+    await expectMissingFlag(
+      processTransaction(provider, new Transaction().add(ix), [newWallet])
+    );
+
+    await distributorW.claim(claimArgs);
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      newWallet.publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(200)
+    );
+  });
+
+  it("screens the destination of a delegated claim", async () => {
+    const delegate = Keypair.generate();
+    await provider.connection.requestAirdrop(
+      delegate.publicKey,
+      LAMPORTS_PER_SOL
+    );
+    const claimAsDelegate = () =>
+      distributorW.claimAsDelegate({
+        index: new anchor.BN(2),
+        amount: new anchor.BN(300),
+        proof: tree.getProof(2, delegator.publicKey, new anchor.BN(300)),
+        claimant: delegator.publicKey,
+        delegate,
+      });
+
+    await sdk.setClaimDelegate({
+      claimant: delegator,
+      delegate: delegate.publicKey,
+      destination: flaggedWallet,
+    });
+    try {
+      await claimAsDelegate();
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(
+        e.msg,
+        "Claimant is flagged by the distributor's screening program"
+      );
+    }
+
+    const destination = Keypair.generate().publicKey;
+    await sdk.setClaimDelegate({
+      claimant: delegator,
+      delegate: delegate.publicKey,
+      destination,
+    });
+    await claimAsDelegate();
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      destination
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(300)
+    );
+  });
+});
@@ -0,0 +1,155 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, PublicKey, Transaction } from "@solana/web3.js";
+import {
+  createAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+
+import { BalanceTree, processTransaction } from "../src/utils";
+import { makeSDK, createAndSeedDistributor, getEvents } from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+import { sleepUntil } from "../deps/zeta-staking/tests/test-utils";
+
+const MAX_NUM_NODES = new anchor.BN(1);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000);
+const EXCESS = new anchor.BN(250);
+
+describe("sweep", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const claimEndTs = Math.round(Date.now() / 1000) + 15;
+  const tree = new BalanceTree([
+    { account: Keypair.generate().publicKey, amount: MAX_TOTAL_CLAIM },
+  ]);
+
+  let distributorW: MerkleDistributorWrapper;
+  let recipient: PublicKey;
+
+  const vaultAmount = async (): Promise<bigint> =>
+    (await getAccount(provider.connection, distributorW.distributorATA))
+      .amount;
+
+  before(async () => {
+    const { mint, distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      Keypair.generate(),
+      new anchor.BN(0),
+      new anchor.BN(claimEndTs),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0)
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+
+    // Tokens sent to the vault beyond what claimants are owed.
+    await mintTo(
+      provider.connection,
+      payer,
+      mint,
+      distributorW.distributorATA,
+      payer,
+      EXCESS.toNumber()
+    );
+
+    recipient = await createAccount(
+      provider.connection,
+      payer,
+      mint,
+      Keypair.generate().publicKey,
+      Keypair.generate()
+    );
+  });
+
+  it("computes the sweepable amount", async () => {
+    const vault = MAX_TOTAL_CLAIM.add(EXCESS);
+    assert.equal(
+      distributorW.getSweepableAmount(vault, claimEndTs).toNumber(),
+      EXCESS.toNumber()
+    );
+    assert.equal(
+      distributorW.getSweepableAmount(vault, claimEndTs + 1).toNumber(),
+      vault.toNumber()
+    );
+  });
+
+  it("admin cannot sweep tokens still owed to claimants", async () => {
+    try {
+      await distributorW.adminSweep(payer, EXCESS.addn(1));
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Amount exceeds what can be swept");
+    }
+  });
+
+  it("admin can sweep the excess during the claim window", async () => {
+    const signature = await distributorW.adminSweep(payer, EXCESS);
+
+    assert.equal(await vaultAmount(), BigInt(MAX_TOTAL_CLAIM.toNumber()));
+    const ata = getAssociatedTokenAddressSync(
+      distributorW.data.mint,
+      payer.publicKey
+    );
+    assert.equal(
+      (await getAccount(provider.connection, ata)).amount,
+      BigInt(EXCESS.toNumber())
+    );
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "SweptEvent");
+    assert.equal(event.data.amount.toNumber(), EXCESS.toNumber());
+    assert.isFalse(event.data.permissionless);
+  });
+
+  it("cannot sweep after expiry without a sweep recipient", async () => {
+    await sleepUntil(claimEndTs + 1);
+
+    const ix = sdk.program.instruction.sweepAfterExpiry({
+      accounts: {
+        distributor: distributorW.key,
+        from: distributorW.distributorATA,
+        to: recipient,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    });
+
+    try {
+      await processTransaction(provider, new Transaction().add(ix));
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "No sweep recipient is set");
+    }
+  });
+
+  it("anyone can sweep to the recipient after expiry", async () => {
+    await distributorW.setSweepRecipient(payer, recipient);
+    await distributorW.reload();
+    assert.isTrue(distributorW.data.sweepRecipient.equals(recipient));
+
+    const signature = await distributorW.sweepAfterExpiry();
+
+    assert.equal(await vaultAmount(), BigInt(0));
+    assert.equal(
+      (await getAccount(provider.connection, recipient)).amount,
+      BigInt(MAX_TOTAL_CLAIM.toNumber())
+    );
+
+    const [event] = await getEvents(sdk, signature);
+    assert.equal(event.name, "SweptEvent");
+    assert.isTrue(event.data.permissionless);
+  });
+
+  it("admin can unset the sweep recipient", async () => {
+    await distributorW.setSweepRecipient(payer, null);
+    await distributorW.reload();
+    assert.isTrue(distributorW.data.sweepRecipient.equals(PublicKey.default));
+  });
+});
@@ -1,10 +1,20 @@
 import * as anchor from "@coral-xyz/anchor";
 
-import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  TransactionSignature,
+} from "@solana/web3.js";
 import { createMint, mintTo } from "@solana/spl-token";
 
 import { MerkleDistributorSDK } from "../src";
 import { Distributor } from "../src";
+import type {
+  HashFunction,
+  HashScheme,
+  LeafVersion,
+} from "../src/utils";
 
 export const DEFAULT_TOKEN_DECIMALS = 6;
 
@@ -25,6 +35,21 @@ export const createKeypairWithSOL = async (
   return kp;
 };
 
+export const getEvents = async (
+  sdk: MerkleDistributorSDK,
+  signature: TransactionSignature
+): Promise<anchor.Event[]> => {
+  const tx = await sdk.provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(
+    sdk.program.programId,
+    sdk.program.coder
+  );
+  return [...parser.parseLogs(tx.meta.logMessages)];
+};
+
 export const createAndSeedDistributor = async (
   sdk: MerkleDistributorSDK,
   maxTotalClaim: anchor.BN,
@@ -37,7 +62,10 @@ export const createAndSeedDistributor = async (
   immediateClaimPercentage: anchor.BN,
   laterClaimOffsetSeconds: anchor.BN,
   mint?: PublicKey,
-  mintAdmin?: Keypair
+  mintAdmin?: Keypair,
+  hashScheme?: HashScheme,
+  leafVersion?: LeafVersion,
+  hashFunction?: HashFunction
 ): Promise<{
   mint: PublicKey;
   distributorKey: PublicKey;
@@ -70,6 +98,9 @@ export const createAndSeedDistributor = async (
     stakeClaimOnly,
     immediateClaimPercentage,
     laterClaimOffsetSeconds,
+    hashScheme,
+    leafVersion,
+    hashFunction,
   });
 
   let payerToUse = (provider.wallet as anchor.Wallet).payer;
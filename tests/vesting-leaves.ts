@@ -0,0 +1,210 @@
+import * as anchor from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  BalanceTree,
+  HASH_SCHEME_LEGACY,
+  LEAF_VERSION_V2,
+  LeafTerms,
+} from "../src/utils";
+import {
+  makeSDK,
+  createAndSeedDistributor,
+  DEFAULT_TOKEN_DECIMALS,
+} from "./utils";
+import { MerkleDistributorWrapper } from "../src";
+import { sleepUntil } from "../deps/zeta-staking/tests/test-utils";
+
+const MAX_NUM_NODES = new anchor.BN(3);
+const MAX_TOTAL_CLAIM = new anchor.BN(1_000 + 2_000 + 3_000);
+
+describe("vesting-leaves", () => {
+  const sdk = makeSDK();
+  const { provider } = sdk;
+
+  const claimStartTs = Math.round(Date.now() / 1000);
+  const claimEndTs = claimStartTs + 1_000_000;
+
+  const kpCliff = Keypair.generate();
+  const kpLinear = Keypair.generate();
+  const kpStake = Keypair.generate();
+  const allKps = [kpCliff, kpLinear, kpStake];
+
+  const distributorBase = Keypair.generate();
+
+  const amountCliff = new anchor.BN(1_000);
+  const amountLinear = new anchor.BN(2_000);
+  const amountStake = new anchor.BN(3_000);
+  const termsCliff: LeafTerms = {
+    unlockTs: new anchor.BN(claimStartTs + 6),
+    vestingDurationSeconds: new anchor.BN(0),
+    stakeDurationEpochs: 0,
+    category: 1,
+  };
+  const termsLinear: LeafTerms = {
+    unlockTs: new anchor.BN(claimStartTs),
+    vestingDurationSeconds: new anchor.BN(1_000_000),
+    stakeDurationEpochs: 0,
+    category: 2,
+  };
+  const termsStake: LeafTerms = {
+    unlockTs: new anchor.BN(0),
+    vestingDurationSeconds: new anchor.BN(0),
+    stakeDurationEpochs: 90,
+    category: 3,
+  };
+
+  let tree: BalanceTree;
+  let distributorW: MerkleDistributorWrapper;
+
+  before(async () => {
+    await Promise.all(
+      allKps.map(async (kp) => {
+        await provider.connection.requestAirdrop(
+          kp.publicKey,
+          LAMPORTS_PER_SOL
+        );
+      })
+    );
+
+    const mint = await createMint(
+      provider.connection,
+      (provider.wallet as anchor.Wallet).payer,
+      provider.publicKey,
+      provider.publicKey,
+      DEFAULT_TOKEN_DECIMALS
+    );
+    tree = new BalanceTree(
+      [
+        { account: kpCliff.publicKey, amount: amountCliff, terms: termsCliff },
+        {
+          account: kpLinear.publicKey,
+          amount: amountLinear,
+          terms: termsLinear,
+        },
+        { account: kpStake.publicKey, amount: amountStake, terms: termsStake },
+      ],
+      HASH_SCHEME_LEGACY,
+      { base: distributorBase.publicKey, mint }
+    );
+    assert.deepEqual(tree.leafVersion, LEAF_VERSION_V2);
+
+    const { distributorKey } = await createAndSeedDistributor(
+      sdk,
+      MAX_TOTAL_CLAIM,
+      MAX_NUM_NODES,
+      tree.getRoot(),
+      distributorBase,
+      new anchor.BN(claimStartTs),
+      new anchor.BN(claimEndTs),
+      false,
+      new anchor.BN(100_000000),
+      new anchor.BN(0),
+      mint,
+      undefined,
+      HASH_SCHEME_LEGACY,
+      tree.leafVersion
+    );
+    distributorW = await sdk.loadDistributor(distributorKey);
+  });
+
+  it("cannot claim without terms", async () => {
+    try {
+      await distributorW.claim({
+        index: new anchor.BN(1),
+        amount: amountLinear,
+        proof: tree.getProof(1, kpLinear.publicKey, amountLinear, termsLinear),
+        claimant: kpLinear.publicKey,
+        signers: [kpLinear],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(
+        e.msg,
+        "Leaf terms do not match the distributor leaf version"
+      );
+    }
+  });
+
+  it("cannot claim before unlock", async () => {
+    try {
+      await distributorW.claim({
+        index: new anchor.BN(0),
+        amount: amountCliff,
+        terms: termsCliff,
+        proof: tree.getProof(0, kpCliff.publicKey, amountCliff, termsCliff),
+        claimant: kpCliff.publicKey,
+        signers: [kpCliff],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "no claimable amount");
+    }
+  });
+
+  it("claims everything after the cliff", async () => {
+    await sleepUntil(termsCliff.unlockTs.toNumber() + 1);
+
+    await distributorW.claim({
+      index: new anchor.BN(0),
+      amount: amountCliff,
+      terms: termsCliff,
+      proof: tree.getProof(0, kpCliff.publicKey, amountCliff, termsCliff),
+      claimant: kpCliff.publicKey,
+      signers: [kpCliff],
+    });
+
+    const tokenAccountInfo = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(distributorW.data.mint, kpCliff.publicKey)
+    );
+    assert.equal(tokenAccountInfo.amount.toString(), amountCliff.toString());
+    await distributorW.reload();
+    assert.equal(distributorW.data.numNodesClaimed.toNumber(), 1);
+  });
+
+  it("claims the vested part of a linear leaf", async () => {
+    await distributorW.claim({
+      index: new anchor.BN(1),
+      amount: amountLinear,
+      terms: termsLinear,
+      proof: tree.getProof(1, kpLinear.publicKey, amountLinear, termsLinear),
+      claimant: kpLinear.publicKey,
+      signers: [kpLinear],
+    });
+
+    const claimStatus = await distributorW.getClaimStatus(kpLinear.publicKey);
+    assert.isTrue(claimStatus.claimedAmount.gtn(0));
+    assert.isTrue(claimStatus.claimedAmount.lt(amountLinear));
+
+    // Not fully claimed yet, so not counted as a node.
+    await distributorW.reload();
+    assert.equal(distributorW.data.numNodesClaimed.toNumber(), 1);
+    assert.equal(
+      distributorW.data.totalAmountClaimed.toString(),
+      amountCliff.add(claimStatus.claimedAmount).toString()
+    );
+  });
+
+  it("cannot claim a leaf requiring stake directly", async () => {
+    try {
+      await distributorW.claim({
+        index: new anchor.BN(2),
+        amount: amountStake,
+        terms: termsStake,
+        proof: tree.getProof(2, kpStake.publicKey, amountStake, termsStake),
+        claimant: kpStake.publicKey,
+        signers: [kpStake],
+      });
+      throw Error("Should not succeed");
+    } catch (e) {
+      assert.equal(e.msg, "Must claim tokens direct to stake");
+    }
+  });
+});